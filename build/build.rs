@@ -2,7 +2,7 @@ use image::{self, GenericImageView, ImageReader};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::{fs, process::Command};
+use std::{collections::HashMap, fs, process::Command};
 
 fn convert_image(file_name: &str) {
     let img = ImageReader::open(format!("assets/{file_name}.png").as_str())
@@ -88,24 +88,36 @@ fn patch_simulator() {
     }
 }
 
-fn convert_tileset() {
-    let img = ImageReader::open(format!("assets/tileset.png").as_str())
+/// Magenta marks the transparent pixels of the atlases
+const TRANSPARENT_COLOR: u16 = 0xF81F;
+
+/// Convert a 128 pixels wide atlas of 8x8 textures to RGB565
+fn convert_atlas(file_name: &str) {
+    let img = ImageReader::open(format!("assets/{file_name}.png").as_str())
         .unwrap()
         .decode()
         .unwrap();
 
+    assert_eq!(
+        img.width(),
+        128,
+        "The atlas {file_name} must be 128 pixels wide"
+    );
+
     let mut data: Vec<u8> = Vec::new();
 
     for pix in img.pixels() {
-        data.extend(
-            (((pix.2.0[0] as u16 & 0b11111000) << 8)
+        let color = if pix.2.0[3] < 128 {
+            TRANSPARENT_COLOR
+        } else {
+            ((pix.2.0[0] as u16 & 0b11111000) << 8)
                 | ((pix.2.0[1] as u16 & 0b11111100) << 3)
-                | (pix.2.0[2] as u16 >> 3))
-                .to_be_bytes(),
-        );
+                | (pix.2.0[2] as u16 >> 3)
+        };
+        data.extend(color.to_be_bytes());
     }
 
-    fs::write(format!("target/assets/tileset.bin").as_str(), data).unwrap();
+    fs::write(format!("target/assets/{file_name}.bin").as_str(), data).unwrap();
 }
 
 fn convert_icon() {
@@ -176,63 +188,331 @@ fn convert_struct(file_name: &str) {
     .expect(format!("Unable to write the structure file for file {}", file_name).as_str());
 }
 
+/// Read the names of the variants of an enum of src/constants.rs. `PlanksBlock = 9` is named `planks_block`.
+fn read_enum_names(constants: &str, enum_name: &str) -> HashMap<String, u8> {
+    let enum_body = Regex::new(&format!(r"pub enum {enum_name} \{{([^}}]*)\}}"))
+        .unwrap()
+        .captures(constants)
+        .unwrap_or_else(|| panic!("Unable to find the {enum_name} enum in src/constants.rs"))[1]
+        .to_string();
+
+    let mut names = HashMap::new();
+    for variant in Regex::new(r"(\w+) = (\d+),")
+        .unwrap()
+        .captures_iter(&enum_body)
+    {
+        let mut name = String::new();
+        for (i, c) in variant[1].chars().enumerate() {
+            if c.is_ascii_uppercase() {
+                if i > 0 {
+                    name.push('_');
+                }
+                name.push(c.to_ascii_lowercase());
+            } else {
+                name.push(c);
+            }
+        }
+        names.insert(name, variant[2].parse().unwrap());
+    }
+
+    names
+}
+
+/// The names of the items and of the item groups, read from src/constants.rs
+struct ItemNames {
+    items: HashMap<String, u8>,
+    groups: HashMap<String, u8>,
+}
+
+fn read_item_names() -> ItemNames {
+    let constants =
+        fs::read_to_string("src/constants.rs").expect("Unable to read the file src/constants.rs");
+
+    ItemNames {
+        items: read_enum_names(&constants, "ItemType"),
+        groups: read_enum_names(&constants, "ItemGroup"),
+    }
+}
+
+fn get_item_id(item_names: &ItemNames, name: &str, file_name: &str) -> u8 {
+    *item_names
+        .items
+        .get(name)
+        .unwrap_or_else(|| panic!("Unknown item name \"{name}\" in file {file_name}"))
+}
+
+/// An ingredient of a craft. Either a name, or the detailed form `{"item": "...", "amount": 2, "leftover": "..."}`.
+/// A name starting with `#` is an item group, like `#planks`.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(untagged)]
+enum IngredientFile {
+    Name(String),
+    Detailed {
+        item: String,
+        #[serde(default = "default_ingredient_amount")]
+        amount: u8,
+        leftover: Option<String>,
+    },
+}
+
+fn default_ingredient_amount() -> u8 {
+    1
+}
+
+/// Return the 4 bytes of the ingredient, read by CraftSlot::new
+fn convert_ingredient(
+    ingredient: &IngredientFile,
+    item_names: &ItemNames,
+    file_name: &str,
+) -> [u8; 4] {
+    let (name, amount, leftover) = match ingredient {
+        IngredientFile::Name(name) => (name, 1, None),
+        IngredientFile::Detailed {
+            item,
+            amount,
+            leftover,
+        } => (item, *amount, leftover.as_ref()),
+    };
+
+    assert!(
+        amount > 0,
+        "The ingredient \"{name}\" must have a positive amount in file {file_name}"
+    );
+
+    let leftover = leftover.map_or(0, |leftover| get_item_id(item_names, leftover, file_name));
+
+    if let Some(group_name) = name.strip_prefix('#') {
+        let group_id = *item_names
+            .groups
+            .get(group_name)
+            .unwrap_or_else(|| panic!("Unknown item group \"{name}\" in file {file_name}"));
+        [2, group_id, amount, leftover]
+    } else {
+        let item_id = get_item_id(item_names, name, file_name);
+        assert!(
+            item_id != 0,
+            "Air can't be an ingredient in file {file_name}"
+        );
+        [1, item_id, amount, leftover]
+    }
+}
+
+/// A shaped craft has a pattern and a palette, a shapeless craft has a list of ingredients
 #[derive(Serialize, Deserialize, Debug)]
 struct CraftFile {
     name: String,
-    pattern: [String; 3],
+    pattern: Option<Vec<String>>,
+    #[serde(default)]
     strict_shape: bool,
-    palette: Value,
-    result: u8,
+    #[serde(default)]
+    palette: HashMap<String, IngredientFile>,
+    ingredients: Option<Vec<IngredientFile>>,
+    result: String,
     result_amount: u8,
 }
 
-fn convert_craft(file_name: &str) {
+/// Return the data of the craft, read by Craft::new
+fn convert_craft(file_name: &str, item_names: &ItemNames) -> (String, Vec<u8>) {
     let raw = fs::read_to_string(file_name)
-        .expect(format!("Unable to read the file {}", file_name).as_str());
+        .unwrap_or_else(|_| panic!("Unable to read the file {file_name}"));
     let craft_file: CraftFile =
-        serde_json::from_str(&raw).expect(format!("Invalid Json for file {}", file_name).as_str());
+        serde_json::from_str(&raw).unwrap_or_else(|_| panic!("Invalid Json for file {file_name}"));
 
     let mut data = Vec::new();
 
-    let palette = craft_file.palette;
+    let shaped = match (&craft_file.pattern, &craft_file.ingredients) {
+        (Some(pattern), None) => {
+            // Check the size of the pattern
+            assert!(
+                pattern.len() == 3 && pattern.iter().all(|line| line.chars().count() == 3),
+                "Invalid pattern in craft for file {}: it must be 3 lines of 3 characters",
+                file_name
+            );
+
+            for line in pattern {
+                for letter in line.chars() {
+                    // Check if the letter is a space
+                    if letter == ' ' {
+                        data.extend([0; 4]);
+                    } else {
+                        let ingredient =
+                            craft_file
+                                .palette
+                                .get(&letter.to_string())
+                                .unwrap_or_else(|| {
+                                    panic!(
+                                        "The character '{letter}' is not in the palette of file {file_name}"
+                                    )
+                                });
+                        data.extend(convert_ingredient(ingredient, item_names, file_name));
+                    }
+                }
+            }
+
+            craft_file.strict_shape
+        }
+        (None, Some(ingredients)) => {
+            assert!(
+                ingredients.len() <= 9,
+                "Too many ingredients in craft for file {}: the grid only has 9 slots",
+                file_name
+            );
+
+            for ingredient in ingredients {
+                data.extend(convert_ingredient(ingredient, item_names, file_name));
+            }
+            data.resize(9 * 4, 0);
 
-    // Check the size of the lines
-    for i in 0..3 {
-        assert_eq!(
-            craft_file.pattern[i].len(),
-            3,
-            "Invalid pattern in craft for file {}",
+            false
+        }
+        _ => panic!(
+            "The craft of file {} must have either a pattern or a list of ingredients",
             file_name
-        );
-    }
+        ),
+    };
 
-    for y in 0..3 {
-        for x in 0..3 {
-            let letter = craft_file.pattern[y].as_bytes()[x];
-            // Check if the letter is a space
-            if letter == ' ' as u8 {
-                data.push(0);
-            } else {
-                let item_id = &palette[str::from_utf8(&[letter])
-                    .expect(format!("Invalid char for craft in file {}", file_name).as_str())];
-                let item_id_u8 = item_id
-                    .as_u64()
-                    .expect(format!("Invalid char for craft in file {}", file_name).as_str())
-                    as u8;
-                data.push(item_id_u8);
+    assert!(
+        data.iter().any(|&byte| byte != 0),
+        "Empty pattern in craft for file {}",
+        file_name
+    );
+
+    data.push(if shaped { 1 } else { 0 });
+    data.push(get_item_id(item_names, &craft_file.result, file_name));
+    data.push(craft_file.result_amount);
+
+    (craft_file.name, data)
+}
+
+/// Return what the player must put in the grid for this craft. Two crafts with the same key are duplicates.
+fn get_craft_key(data: &[u8]) -> Vec<[u8; 4]> {
+    let pattern: Vec<[u8; 4]> = data[0..36]
+        .chunks(4)
+        .map(|ingredient| ingredient.try_into().unwrap())
+        .collect();
+    let used = |x: usize, y: usize| pattern[x + y * 3][0] != 0;
+
+    if data[36] == 1 {
+        // The shape can be placed anywhere in the grid, so only keep the smallest rectangle around it
+        let columns: Vec<usize> = (0..3).filter(|&x| (0..3).any(|y| used(x, y))).collect();
+        let lines: Vec<usize> = (0..3).filter(|&y| (0..3).any(|x| used(x, y))).collect();
+
+        let mut key = vec![[1; 4]];
+        for y in lines[0]..=lines[lines.len() - 1] {
+            for x in columns[0]..=columns[columns.len() - 1] {
+                key.push(pattern[x + y * 3]);
             }
+            key.push([u8::MAX; 4]);
         }
+        key
+    } else {
+        let mut key = vec![[0; 4]];
+        key.extend(pattern.iter().filter(|ingredient| ingredient[0] != 0));
+        key[1..].sort();
+        key
     }
+}
 
-    data.push(if craft_file.strict_shape { 1 } else { 0 });
-    data.push(craft_file.result);
-    data.push(craft_file.result_amount);
+/// Convert all the crafts and generate the CRAFTS table included by the crafting manager
+fn generate_crafts(item_names: &ItemNames) {
+    let mut files: Vec<_> = fs::read_dir("crafts")
+        .unwrap()
+        .map(|file| file.expect("Invalid file in crafts directory.").path())
+        .collect();
+    files.sort();
 
-    fs::write(
-        format!("target/crafts/{}.bin", craft_file.name).to_string(),
-        data,
-    )
-    .expect(format!("Unable to write the crafts file for file {}", file_name).as_str());
+    let mut crafts: Vec<(String, Vec<u8>)> = Vec::new();
+    for file in files {
+        let file_name = file.as_os_str().to_str().unwrap();
+        let (name, data) = convert_craft(file_name, item_names);
+
+        if let Some((other, _)) = crafts.iter().find(|(other, _)| *other == name) {
+            panic!("Duplicate craft name \"{other}\" in file {file_name}");
+        }
+        if let Some((other, _)) = crafts
+            .iter()
+            .find(|(_, other_data)| get_craft_key(other_data) == get_craft_key(&data))
+        {
+            panic!("The crafts \"{other}\" and \"{name}\" have the same recipe");
+        }
+
+        crafts.push((name, data));
+    }
+
+    let mut table =
+        String::from("// Generated by build.rs from the files of the crafts directory\n");
+    table += &format!("const CRAFTS: [Craft; {}] = [\n", crafts.len());
+    for (name, data) in crafts {
+        table += &format!("    // {name}\n    Craft::new(&{data:?}),\n");
+    }
+    table += "];\n";
+
+    fs::write("target/crafts/crafts.rs", table).expect("Unable to write the crafts table");
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct SmeltingFile {
+    name: String,
+    input: String,
+    result: String,
+    result_amount: u8,
+}
+
+/// Return the name of the smelting recipe and its data, read by SmeltingRecipe::new
+fn convert_smelting(file_name: &str, item_names: &ItemNames) -> (String, [u8; 3]) {
+    let raw = fs::read_to_string(file_name)
+        .unwrap_or_else(|_| panic!("Unable to read the file {file_name}"));
+    let smelting_file: SmeltingFile =
+        serde_json::from_str(&raw).unwrap_or_else(|_| panic!("Invalid Json for file {file_name}"));
+
+    let data = [
+        get_item_id(item_names, &smelting_file.input, file_name),
+        get_item_id(item_names, &smelting_file.result, file_name),
+        smelting_file.result_amount,
+    ];
+
+    (smelting_file.name, data)
+}
+
+/// Convert all the smelting recipes and generate the SMELTING_RECIPES table included by the furnaces
+fn generate_smelting_recipes(item_names: &ItemNames) {
+    let mut files: Vec<_> = fs::read_dir("smelting")
+        .unwrap()
+        .map(|file| file.expect("Invalid file in smelting directory.").path())
+        .collect();
+    files.sort();
+
+    let mut recipes: Vec<(String, [u8; 3])> = Vec::new();
+    for file in files {
+        let file_name = file.as_os_str().to_str().unwrap();
+        let (name, data) = convert_smelting(file_name, item_names);
+
+        if let Some((other, _)) = recipes.iter().find(|(other, _)| *other == name) {
+            panic!("Duplicate smelting recipe name \"{other}\" in file {file_name}");
+        }
+        if let Some((other, _)) = recipes
+            .iter()
+            .find(|(_, other_data)| other_data[0] == data[0])
+        {
+            panic!("The smelting recipes \"{other}\" and \"{name}\" have the same input");
+        }
+
+        recipes.push((name, data));
+    }
+
+    let mut table =
+        String::from("// Generated by build.rs from the files of the smelting directory\n");
+    table += &format!(
+        "const SMELTING_RECIPES: [SmeltingRecipe; {}] = [\n",
+        recipes.len()
+    );
+    for (name, data) in recipes {
+        table += &format!("    // {name}\n    SmeltingRecipe::new(&{data:?}),\n");
+    }
+    table += "];\n";
+
+    fs::write("target/smelting/smelting.rs", table)
+        .expect("Unable to write the smelting recipes table");
 }
 
 fn main() {
@@ -250,7 +530,11 @@ fn main() {
 
     // Convert tileset
     println!("cargo:rerun-if-changed=assets/tileset.png");
-    convert_tileset();
+    convert_atlas("tileset");
+
+    // Convert the sprites of the items that are not blocks
+    println!("cargo:rerun-if-changed=assets/items.png");
+    convert_atlas("items");
 
     println!("cargo:rerun-if-changed=structs");
 
@@ -264,17 +548,15 @@ fn main() {
         );
     }
 
+    // The recipes reference the items by the names of the ItemType enum
+    println!("cargo:rerun-if-changed=src/constants.rs");
+    let item_names = read_item_names();
+
     println!("cargo:rerun-if-changed=crafts");
+    generate_crafts(&item_names);
 
-    for file in fs::read_dir("crafts").unwrap() {
-        convert_craft(
-            file.expect("Invalid file in struct directory.")
-                .path()
-                .as_os_str()
-                .to_str()
-                .unwrap(),
-        );
-    }
+    println!("cargo:rerun-if-changed=smelting");
+    generate_smelting_recipes(&item_names);
 
     // Compile storage.c
     if std::env::var("CARGO_CFG_TARGET_OS").unwrap() == "none" {
@@ -1,7 +1,15 @@
 use nalgebra::Vector3;
 use serde::{Deserialize, Serialize};
 
-use crate::{nadk::display::Color565, physic::BoundingBox, renderer::mesh::QuadDir};
+use crate::{
+    nadk::display::Color565,
+    physic::BoundingBox,
+    renderer::mesh::QuadDir,
+    world::{
+        block_shape::BlockShape, block_state::BlockState, furnace::LIT_FURNACE_VALUE,
+        redstone::REPEATER_POWERED_BIT,
+    },
+};
 
 pub mod rendering {
     pub const SCREEN_WIDTH: usize = 320;
@@ -40,12 +48,24 @@ pub mod color_palette {
 
     pub const GAMEUI_SLOT_COLOR: Color565 = Color565::from_rgb888(80, 80, 80);
     pub const GAMEUI_SLOT_DEFAULT_OUTLINE_COLOR: Color565 = Color565::from_rgb888(120, 120, 120);
+    pub const GAMEUI_SLOT_HIGHLIGHTED_OUTLINE_COLOR: Color565 = Color565::from_rgb888(60, 200, 60);
 }
 
 pub mod save_manager {
     pub const SETTINGS_FILENAME: &str = "settings.ncd"; // NCD = NumCraftData
 
-    pub const WORLD_VERSION: u16 = 0; // Update the version at each world breaking update
+    pub const WORLD_VERSION: u16 = 5; // Update the version at each world breaking update
+
+    /// First world version saving the damage of the tools
+    pub const TOOLS_WORLD_VERSION: u16 = 1;
+    /// First world version saving the item metadata
+    pub const ITEM_METADATA_WORLD_VERSION: u16 = 2;
+    /// First world version saving the health of the player and its spawn position
+    pub const HEALTH_WORLD_VERSION: u16 = 3;
+    /// First world version saving the hunger of the player
+    pub const HUNGER_WORLD_VERSION: u16 = 4;
+    /// First world version saving the entities other than the player
+    pub const ENTITIES_WORLD_VERSION: u16 = 5;
 }
 
 pub mod world {
@@ -54,6 +74,40 @@ pub mod world {
     pub const MAX_ITEM_MERGING_DISTANCE: f32 = 2.;
     pub const ITEM_MAGNET_FORCE: f32 = 10.;
     pub const MAX_PLAYER_ITEM_MAGNET_DISTANCE: f32 = 2.2;
+    pub const ITEM_PICKUP_DELAY: f32 = 1.5; // In seconds, before a thrown item can be picked up
+    pub const HURT_DURATION: f32 = 0.3; // In seconds, the entity is drawn in red and can't walk
+
+    pub const RANDOM_TICKS_PER_CHUNK: f32 = 4.; // Per second
+    pub const LEAVES_DECAY_LOG_DISTANCE: isize = 3;
+    pub const SAPLING_DROP_CHANCE: u32 = 5; // 1 in SAPLING_DROP_CHANCE
+    pub const SAPLING_GROWTH_STAGES: u8 = 3;
+    pub const IRON_ORE_CHANCE: u32 = 40; // 1 in IRON_ORE_CHANCE stone blocks
+    pub const WILD_CARROTS_CHANCE: u32 = 48; // 1 in WILD_CARROTS_CHANCE grass blocks
+    pub const APPLE_DROP_CHANCE: u32 = 8; // 1 in APPLE_DROP_CHANCE decaying leaves
+    pub const CROP_GROWTH_STAGES: u8 = 4;
+    pub const CARROTS_HARVEST_AMOUNT: u8 = 3; // Dropped by fully grown carrots
+
+    pub const BLOCK_TICK_DURATION: f32 = 0.1; // In seconds
+    pub const FALLING_BLOCK_DELAY: u32 = 1; // In block ticks
+    pub const LEAVES_DECAY_MAX_DELAY: u32 = 40; // In block ticks
+
+    pub const TNT_FUSE_DURATION: f32 = 4.; // In seconds
+    pub const TNT_CHAIN_MIN_FUSE_DURATION: f32 = 0.5; // In seconds
+    pub const TNT_CHAIN_MAX_FUSE_DURATION: f32 = 1.5; // In seconds
+    pub const TNT_PRIMING_JUMP_VELOCITY: f32 = 2.;
+    pub const TNT_EXPLOSION_POWER: f32 = 4.;
+    pub const EXPLOSION_DROP_CHANCE: u32 = 3; // 1 in EXPLOSION_DROP_CHANCE
+    pub const EXPLOSION_KNOCKBACK: f32 = 12.;
+
+    pub const REDSTONE_TORCH_DELAY: u32 = 1; // In block ticks
+    pub const BUTTON_PRESS_DURATION: u32 = 10; // In block ticks
+
+    pub const CHEST_INVENTORY_SIZE: usize = 18;
+    pub const FURNACE_INVENTORY_SIZE: usize = 3; // Input, fuel and output
+    pub const SMELTING_DURATION: f32 = 5.; // In seconds
+
+    pub const MAX_LIGHT_LEVEL: u8 = 15;
+    pub const MAX_LIGHT_SEARCH_BLOCKS: usize = 256; // Blocks further from the sky are in the dark
 }
 
 pub mod player {
@@ -64,6 +118,34 @@ pub mod player {
     pub const WALK_FORCE: f32 = 20.0;
     pub const MAX_WALKING_VELOCITY: f32 = 4.;
     pub const JUMP_FORCE: f32 = 5.;
+
+    pub const HOTBAR_SIZE: usize = 6; // The first slots of the player inventory
+    pub const ITEM_THROW_VELOCITY: f32 = 5.;
+
+    pub const ATTACK_REACH: f32 = 4.; // In blocks
+    pub const ATTACK_KNOCKBACK: f32 = 6.;
+    pub const ATTACK_KNOCKBACK_UP: f32 = 3.;
+    pub const ATTACK_EXHAUSTION: f32 = 0.1;
+
+    pub const WRONG_TOOL_MINING_PENALTY: f32 = 3.; // Mining time factor when the block can't be harvested
+
+    pub const MAX_HEALTH: u8 = 20; // In half hearts
+    pub const SAFE_FALL_DISTANCE: f32 = 3.; // In blocks, the player takes 1 damage per block after it
+    pub const ENVIRONMENT_DAMAGE_INTERVAL: f32 = 0.5; // In seconds
+    pub const SUFFOCATION_DAMAGE: u8 = 1;
+    pub const VOID_DAMAGE: u8 = 4;
+    pub const VOID_HEIGHT: f32 = -16.;
+
+    pub const MAX_HUNGER: u8 = 20; // In half food icons
+    pub const EXHAUSTION_PER_HUNGER_POINT: f32 = 4.;
+    pub const MOVEMENT_EXHAUSTION: f32 = 0.02; // Per block
+    pub const JUMP_EXHAUSTION: f32 = 0.1;
+    pub const MINING_EXHAUSTION: f32 = 0.05; // Per block
+    pub const REGENERATION_EXHAUSTION: f32 = 3.; // Per health point
+    pub const REGENERATION_MIN_HUNGER: u8 = 18;
+    pub const HUNGER_EFFECTS_INTERVAL: f32 = 4.; // In seconds, for the regeneration and the starvation
+    pub const STARVATION_DAMAGE: u8 = 1;
+    pub const EATING_DURATION: f32 = 1.6; // In seconds
 }
 
 pub mod physic {
@@ -74,13 +156,68 @@ pub mod physic {
     pub const ON_FLOOR_FRICTION: f32 = 10.;
 
     pub const BLOCK_COLLISION_SCANNING_SIZE: Vector3<isize> = Vector3::new(2, 3, 2);
+
+    pub const MAX_STEP_HEIGHT: f32 = 0.5;
+}
+
+pub mod mob {
+    pub const MAX_PASSIVE_MOBS: usize = 10; // Around the player
+    pub const MAX_HOSTILE_MOBS: usize = 4; // Around the player
+    pub const SPAWN_INTERVAL: f32 = 4.; // In seconds, between two natural spawn attempts
+    pub const MIN_SPAWN_DISTANCE: f32 = 10.; // In blocks, from the player
+    pub const MAX_SPAWN_DISTANCE: f32 = 20.; // In blocks, from the player
+    pub const DESPAWN_DISTANCE: f32 = 28.; // In blocks, from the player
+    pub const GENERATION_GROUP_CHANCE: u32 = 4; // 1 in GENERATION_GROUP_CHANCE generated chunk columns
+    pub const MAX_GROUP_SIZE: u32 = 3;
+    pub const GROUP_SPREAD: isize = 2; // In blocks, around the center of the group
+
+    pub const WALK_FORCE: f32 = 20.;
+    pub const JUMP_FORCE: f32 = 5.;
+    pub const MIN_WANDER_DURATION: f32 = 2.; // In seconds
+    pub const MAX_WANDER_DURATION: f32 = 6.; // In seconds
+    pub const WANDER_IDLE_CHANCE: f32 = 0.4; // Chance to stand still instead of walking
+    pub const PANIC_DURATION: f32 = 5.; // In seconds, time a mob flees after being hurt
+    pub const FLEE_DISTANCE: f32 = 8.; // In blocks
+
+    pub const PATHFINDING_BUDGET: usize = 24; // Nodes explored per frame, shared by all the mobs
+    pub const MAX_PATH_NODES: usize = 160; // The search gives up after exploring this many nodes
+    pub const MAX_PATH_DROP: isize = 3; // In blocks, mobs don't jump down from higher
+    pub const REPATH_INTERVAL: f32 = 1.; // In seconds
+    pub const WAYPOINT_REACHED_DISTANCE: f32 = 0.3; // In blocks
+
+    pub const PASSIVE_MIN_SPAWN_LIGHT: u8 = 9;
+    pub const HOSTILE_MAX_SPAWN_LIGHT: u8 = 7; // There is no night, hostile mobs only spawn in caves and closed rooms
+
+    pub const CONTACT_ATTACK_INTERVAL: f32 = 1.; // In seconds
+    pub const CONTACT_KNOCKBACK: f32 = 4.;
+
+    pub const PIG_MAX_HEALTH: u8 = 10; // In half hearts
+    pub const PIG_SPEED: f32 = 1.5;
+    pub const PIG_PANIC_SPEED: f32 = 2.5;
+    pub const PIG_MAX_DROPS: u8 = 3;
+
+    pub const ZOMBIE_MAX_HEALTH: u8 = 20; // In half hearts
+    pub const ZOMBIE_SPEED: f32 = 2.;
+    pub const ZOMBIE_DETECTION_DISTANCE: f32 = 16.; // In blocks
+    pub const ZOMBIE_ATTACK_DAMAGE: u8 = 3; // In half hearts
+    pub const ZOMBIE_MAX_DROPS: u8 = 2;
 }
 
 #[allow(unreachable_patterns)]
 impl EntityType {
+    /// Return the health of the entity when it spawns. Return None if the entity can't take damage.
+    pub fn get_max_health(&self) -> Option<u8> {
+        match self {
+            EntityType::Player => Some(player::MAX_HEALTH),
+            EntityType::Pig => Some(mob::PIG_MAX_HEALTH),
+            EntityType::Zombie => Some(mob::ZOMBIE_MAX_HEALTH),
+            _ => None,
+        }
+    }
+
     pub fn get_bbox(&self) -> Option<BoundingBox> {
         match self {
-            EntityType::Player => Some(BoundingBox {
+            EntityType::Player | EntityType::Zombie => Some(BoundingBox {
                 offset: Vector3::new(-0.4, -0.5, -0.4),
                 size: Vector3::new(0.8, 1.8, 0.8),
             }),
@@ -88,17 +225,30 @@ impl EntityType {
                 offset: Vector3::new(-0.2, -0.2, -0.2),
                 size: Vector3::new(0.4, 0.4, 0.4),
             }),
+            // A bit smaller than a block to fall in one block wide holes
+            EntityType::FallingBlock | EntityType::PrimedTnt => Some(BoundingBox {
+                offset: Vector3::new(-0.49, -0.5, -0.49),
+                size: Vector3::new(0.98, 0.98, 0.98),
+            }),
+            EntityType::Pig => Some(BoundingBox {
+                offset: Vector3::new(-0.45, -0.5, -0.45),
+                size: Vector3::new(0.9, 0.9, 0.9),
+            }),
         }
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum EntityType {
     Player = 0,
     Item = 1,
+    FallingBlock = 2,
+    PrimedTnt = 3,
+    Pig = 4,
+    Zombie = 5,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub enum BlockType {
     Air = 0,
     Stone = 1,
@@ -110,6 +260,24 @@ pub enum BlockType {
     Log = 7,
     Leaves = 8,
     Planks = 9,
+    PlanksSlab = 10,
+    CobblestoneSlab = 11,
+    PlanksStairs = 12,
+    CobblestoneStairs = 13,
+    Fence = 14,
+    Sapling = 15,
+    Tnt = 16,
+    RedstoneWire = 17,
+    Lever = 18,
+    RedstoneTorch = 19,
+    Button = 20,
+    Repeater = 21,
+    RedstoneLamp = 22,
+    Chest = 23,
+    CraftingTable = 24,
+    Furnace = 25,
+    IronOre = 26,
+    Carrots = 27,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
@@ -126,23 +294,98 @@ pub enum ItemType {
     LogBlock = 7,
     LeavesBlock = 8,
     PlanksBlock = 9,
+    PlanksSlabBlock = 10,
+    CobblestoneSlabBlock = 11,
+    PlanksStairsBlock = 12,
+    CobblestoneStairsBlock = 13,
+    FenceBlock = 14,
+    SaplingBlock = 15,
+    TntBlock = 16,
+    RedstoneWireBlock = 17,
+    LeverBlock = 18,
+    RedstoneTorchBlock = 19,
+    ButtonBlock = 20,
+    RepeaterBlock = 21,
+    RedstoneLampBlock = 22,
+    ChestBlock = 23,
+    CraftingTableBlock = 24,
+    FurnaceBlock = 25,
+    IronOreBlock = 26,
+
+    // Items that can't be placed
+    Stick = 128,
+    IronIngot = 129,
+    WoodenPickaxe = 130,
+    StonePickaxe = 131,
+    IronPickaxe = 132,
+    WoodenAxe = 133,
+    StoneAxe = 134,
+    IronAxe = 135,
+    WoodenShovel = 136,
+    StoneShovel = 137,
+    IronShovel = 138,
+    WoodenSword = 139,
+    StoneSword = 140,
+    IronSword = 141,
+    Carrot = 142,
+    Apple = 143,
+    Porkchop = 144,
+    RottenFlesh = 145,
 }
 
 impl ItemType {
-    pub fn get_texture_id(&self) -> u8 {
-        match *self {
-            ItemType::Air => 0,
+    /// Return the icon of the item. Return None for air.
+    pub fn get_texture(&self) -> Option<ItemTexture> {
+        let texture = match *self {
+            ItemType::Air => return None,
 
-            ItemType::StoneBlock => 1,
-            ItemType::GrassBlock => 2,
-            ItemType::DirtBlock => 3, // 4 is the other texture of the grass block
-            ItemType::SandBlock => 5,
-            ItemType::CobblestoneBlock => 6,
-            ItemType::BorderBlock => 7,
-            ItemType::LogBlock => 8,
-            ItemType::LeavesBlock => 9,
-            ItemType::PlanksBlock => 10,
-        }
+            ItemType::StoneBlock => ItemTexture::Tile(1),
+            ItemType::GrassBlock => ItemTexture::Tile(2),
+            ItemType::DirtBlock => ItemTexture::Tile(3), // 4 is the other texture of the grass block
+            ItemType::SandBlock => ItemTexture::Tile(5),
+            ItemType::CobblestoneBlock => ItemTexture::Tile(6),
+            ItemType::BorderBlock => ItemTexture::Tile(7),
+            ItemType::LogBlock => ItemTexture::Tile(8),
+            ItemType::LeavesBlock => ItemTexture::Tile(9),
+            ItemType::PlanksBlock => ItemTexture::Tile(10),
+            ItemType::PlanksSlabBlock => ItemTexture::Tile(11),
+            ItemType::CobblestoneSlabBlock => ItemTexture::Tile(12),
+            ItemType::PlanksStairsBlock => ItemTexture::Tile(13),
+            ItemType::CobblestoneStairsBlock => ItemTexture::Tile(14),
+            ItemType::FenceBlock => ItemTexture::Tile(15),
+            ItemType::SaplingBlock => ItemTexture::Tile(17),
+            ItemType::TntBlock => ItemTexture::Tile(18),
+            ItemType::RedstoneWireBlock => ItemTexture::Tile(20),
+            ItemType::LeverBlock => ItemTexture::Tile(21),
+            ItemType::RedstoneTorchBlock => ItemTexture::Tile(22),
+            ItemType::ButtonBlock => ItemTexture::Tile(23),
+            ItemType::RepeaterBlock => ItemTexture::Tile(24),
+            ItemType::RedstoneLampBlock => ItemTexture::Tile(25),
+            ItemType::ChestBlock => ItemTexture::Tile(26),
+            ItemType::CraftingTableBlock => ItemTexture::Tile(28),
+            ItemType::FurnaceBlock => ItemTexture::Tile(30),
+            ItemType::IronOreBlock => ItemTexture::Tile(32),
+
+            ItemType::Stick => ItemTexture::Sprite(0),
+            ItemType::IronIngot => ItemTexture::Sprite(1),
+            ItemType::WoodenPickaxe => ItemTexture::Sprite(2),
+            ItemType::StonePickaxe => ItemTexture::Sprite(3),
+            ItemType::IronPickaxe => ItemTexture::Sprite(4),
+            ItemType::WoodenAxe => ItemTexture::Sprite(5),
+            ItemType::StoneAxe => ItemTexture::Sprite(6),
+            ItemType::IronAxe => ItemTexture::Sprite(7),
+            ItemType::WoodenShovel => ItemTexture::Sprite(8),
+            ItemType::StoneShovel => ItemTexture::Sprite(9),
+            ItemType::IronShovel => ItemTexture::Sprite(10),
+            ItemType::WoodenSword => ItemTexture::Sprite(11),
+            ItemType::StoneSword => ItemTexture::Sprite(12),
+            ItemType::IronSword => ItemTexture::Sprite(13),
+            ItemType::Carrot => ItemTexture::Sprite(14),
+            ItemType::Apple => ItemTexture::Sprite(15),
+            ItemType::Porkchop => ItemTexture::Sprite(16),
+            ItemType::RottenFlesh => ItemTexture::Sprite(19),
+        };
+        Some(texture)
     }
 
     pub const fn get_from_id(id: u8) -> Option<Self> {
@@ -158,6 +401,42 @@ impl ItemType {
             7 => Some(ItemType::LogBlock),
             8 => Some(ItemType::LeavesBlock),
             9 => Some(ItemType::PlanksBlock),
+            10 => Some(ItemType::PlanksSlabBlock),
+            11 => Some(ItemType::CobblestoneSlabBlock),
+            12 => Some(ItemType::PlanksStairsBlock),
+            13 => Some(ItemType::CobblestoneStairsBlock),
+            14 => Some(ItemType::FenceBlock),
+            15 => Some(ItemType::SaplingBlock),
+            16 => Some(ItemType::TntBlock),
+            17 => Some(ItemType::RedstoneWireBlock),
+            18 => Some(ItemType::LeverBlock),
+            19 => Some(ItemType::RedstoneTorchBlock),
+            20 => Some(ItemType::ButtonBlock),
+            21 => Some(ItemType::RepeaterBlock),
+            22 => Some(ItemType::RedstoneLampBlock),
+            23 => Some(ItemType::ChestBlock),
+            24 => Some(ItemType::CraftingTableBlock),
+            25 => Some(ItemType::FurnaceBlock),
+            26 => Some(ItemType::IronOreBlock),
+
+            128 => Some(ItemType::Stick),
+            129 => Some(ItemType::IronIngot),
+            130 => Some(ItemType::WoodenPickaxe),
+            131 => Some(ItemType::StonePickaxe),
+            132 => Some(ItemType::IronPickaxe),
+            133 => Some(ItemType::WoodenAxe),
+            134 => Some(ItemType::StoneAxe),
+            135 => Some(ItemType::IronAxe),
+            136 => Some(ItemType::WoodenShovel),
+            137 => Some(ItemType::StoneShovel),
+            138 => Some(ItemType::IronShovel),
+            139 => Some(ItemType::WoodenSword),
+            140 => Some(ItemType::StoneSword),
+            141 => Some(ItemType::IronSword),
+            142 => Some(ItemType::Carrot),
+            143 => Some(ItemType::Apple),
+            144 => Some(ItemType::Porkchop),
+            145 => Some(ItemType::RottenFlesh),
             _ => None,
         }
     }
@@ -174,6 +453,42 @@ impl ItemType {
             ItemType::LogBlock => 64,
             ItemType::LeavesBlock => 64,
             ItemType::PlanksBlock => 64,
+            ItemType::PlanksSlabBlock => 64,
+            ItemType::CobblestoneSlabBlock => 64,
+            ItemType::PlanksStairsBlock => 64,
+            ItemType::CobblestoneStairsBlock => 64,
+            ItemType::FenceBlock => 64,
+            ItemType::SaplingBlock => 64,
+            ItemType::TntBlock => 64,
+            ItemType::RedstoneWireBlock => 64,
+            ItemType::LeverBlock => 64,
+            ItemType::RedstoneTorchBlock => 64,
+            ItemType::ButtonBlock => 64,
+            ItemType::RepeaterBlock => 64,
+            ItemType::RedstoneLampBlock => 64,
+            ItemType::ChestBlock => 64,
+            ItemType::CraftingTableBlock => 64,
+            ItemType::FurnaceBlock => 64,
+            ItemType::IronOreBlock => 64,
+            ItemType::Stick => 64,
+            ItemType::IronIngot => 64,
+            ItemType::Carrot => 64,
+            ItemType::Apple => 64,
+            ItemType::Porkchop => 64,
+            ItemType::RottenFlesh => 64,
+            // Tools don't stack
+            ItemType::WoodenPickaxe
+            | ItemType::StonePickaxe
+            | ItemType::IronPickaxe
+            | ItemType::WoodenAxe
+            | ItemType::StoneAxe
+            | ItemType::IronAxe
+            | ItemType::WoodenShovel
+            | ItemType::StoneShovel
+            | ItemType::IronShovel
+            | ItemType::WoodenSword
+            | ItemType::StoneSword
+            | ItemType::IronSword => 1,
         }
     }
 
@@ -189,8 +504,333 @@ impl ItemType {
             ItemType::LogBlock => Some(BlockType::Log),
             ItemType::LeavesBlock => Some(BlockType::Leaves),
             ItemType::PlanksBlock => Some(BlockType::Planks),
+            ItemType::PlanksSlabBlock => Some(BlockType::PlanksSlab),
+            ItemType::CobblestoneSlabBlock => Some(BlockType::CobblestoneSlab),
+            ItemType::PlanksStairsBlock => Some(BlockType::PlanksStairs),
+            ItemType::CobblestoneStairsBlock => Some(BlockType::CobblestoneStairs),
+            ItemType::FenceBlock => Some(BlockType::Fence),
+            ItemType::SaplingBlock => Some(BlockType::Sapling),
+            ItemType::TntBlock => Some(BlockType::Tnt),
+            ItemType::RedstoneWireBlock => Some(BlockType::RedstoneWire),
+            ItemType::LeverBlock => Some(BlockType::Lever),
+            ItemType::RedstoneTorchBlock => Some(BlockType::RedstoneTorch),
+            ItemType::ButtonBlock => Some(BlockType::Button),
+            ItemType::RepeaterBlock => Some(BlockType::Repeater),
+            ItemType::RedstoneLampBlock => Some(BlockType::RedstoneLamp),
+            ItemType::ChestBlock => Some(BlockType::Chest),
+            ItemType::CraftingTableBlock => Some(BlockType::CraftingTable),
+            ItemType::FurnaceBlock => Some(BlockType::Furnace),
+            ItemType::IronOreBlock => Some(BlockType::IronOre),
+            // Carrots are planted
+            ItemType::Carrot => Some(BlockType::Carrots),
+            _ => None,
+        }
+    }
+
+    /// Return the name of the item, used to search it in the creative inventory
+    pub const fn get_name(&self) -> &'static str {
+        match self {
+            ItemType::Air => "Air",
+            ItemType::StoneBlock => "Stone",
+            ItemType::GrassBlock => "Grass",
+            ItemType::DirtBlock => "Dirt",
+            ItemType::SandBlock => "Sand",
+            ItemType::CobblestoneBlock => "Cobblestone",
+            ItemType::BorderBlock => "Border",
+            ItemType::LogBlock => "Log",
+            ItemType::LeavesBlock => "Leaves",
+            ItemType::PlanksBlock => "Planks",
+            ItemType::PlanksSlabBlock => "Planks slab",
+            ItemType::CobblestoneSlabBlock => "Cobblestone slab",
+            ItemType::PlanksStairsBlock => "Planks stairs",
+            ItemType::CobblestoneStairsBlock => "Cobblestone stairs",
+            ItemType::FenceBlock => "Fence",
+            ItemType::SaplingBlock => "Sapling",
+            ItemType::TntBlock => "TNT",
+            ItemType::RedstoneWireBlock => "Redstone wire",
+            ItemType::LeverBlock => "Lever",
+            ItemType::RedstoneTorchBlock => "Redstone torch",
+            ItemType::ButtonBlock => "Button",
+            ItemType::RepeaterBlock => "Repeater",
+            ItemType::RedstoneLampBlock => "Redstone lamp",
+            ItemType::ChestBlock => "Chest",
+            ItemType::CraftingTableBlock => "Crafting table",
+            ItemType::FurnaceBlock => "Furnace",
+            ItemType::IronOreBlock => "Iron ore",
+            ItemType::Stick => "Stick",
+            ItemType::IronIngot => "Iron ingot",
+            ItemType::WoodenPickaxe => "Wooden pickaxe",
+            ItemType::StonePickaxe => "Stone pickaxe",
+            ItemType::IronPickaxe => "Iron pickaxe",
+            ItemType::WoodenAxe => "Wooden axe",
+            ItemType::StoneAxe => "Stone axe",
+            ItemType::IronAxe => "Iron axe",
+            ItemType::WoodenShovel => "Wooden shovel",
+            ItemType::StoneShovel => "Stone shovel",
+            ItemType::IronShovel => "Iron shovel",
+            ItemType::WoodenSword => "Wooden sword",
+            ItemType::StoneSword => "Stone sword",
+            ItemType::IronSword => "Iron sword",
+            ItemType::Carrot => "Carrot",
+            ItemType::Apple => "Apple",
+            ItemType::Porkchop => "Porkchop",
+            ItemType::RottenFlesh => "Rotten flesh",
+        }
+    }
+
+    /// Return the tab of the creative inventory showing the item. Return None if the item can't be taken in creative.
+    pub const fn get_creative_category(&self) -> Option<CreativeCategory> {
+        match self {
+            ItemType::StoneBlock
+            | ItemType::CobblestoneBlock
+            | ItemType::PlanksBlock
+            | ItemType::PlanksSlabBlock
+            | ItemType::CobblestoneSlabBlock
+            | ItemType::PlanksStairsBlock
+            | ItemType::CobblestoneStairsBlock
+            | ItemType::FenceBlock
+            | ItemType::ChestBlock
+            | ItemType::CraftingTableBlock
+            | ItemType::FurnaceBlock => Some(CreativeCategory::Building),
+            ItemType::GrassBlock
+            | ItemType::DirtBlock
+            | ItemType::SandBlock
+            | ItemType::LogBlock
+            | ItemType::LeavesBlock
+            | ItemType::SaplingBlock
+            | ItemType::IronOreBlock
+            | ItemType::Carrot
+            | ItemType::Apple
+            | ItemType::Porkchop
+            | ItemType::RottenFlesh => Some(CreativeCategory::Nature),
+            ItemType::TntBlock
+            | ItemType::RedstoneWireBlock
+            | ItemType::LeverBlock
+            | ItemType::RedstoneTorchBlock
+            | ItemType::ButtonBlock
+            | ItemType::RepeaterBlock
+            | ItemType::RedstoneLampBlock => Some(CreativeCategory::Redstone),
+            ItemType::Stick
+            | ItemType::IronIngot
+            | ItemType::WoodenPickaxe
+            | ItemType::StonePickaxe
+            | ItemType::IronPickaxe
+            | ItemType::WoodenAxe
+            | ItemType::StoneAxe
+            | ItemType::IronAxe
+            | ItemType::WoodenShovel
+            | ItemType::StoneShovel
+            | ItemType::IronShovel
+            | ItemType::WoodenSword
+            | ItemType::StoneSword
+            | ItemType::IronSword => Some(CreativeCategory::Tools),
+            ItemType::Air | ItemType::BorderBlock => None,
+        }
+    }
+
+    /// Return the hunger points restored by eating the item. Return None if the item can't be eaten.
+    pub const fn get_food_value(&self) -> Option<u8> {
+        match self {
+            ItemType::Carrot => Some(3),
+            ItemType::Apple => Some(4),
+            ItemType::Porkchop => Some(3),
+            ItemType::RottenFlesh => Some(2),
+            _ => None,
+        }
+    }
+
+    /// Return the kind and the tier of the tool. Return None if the item is not a tool.
+    pub const fn get_tool(&self) -> Option<(ToolKind, ToolTier)> {
+        match self {
+            ItemType::WoodenPickaxe => Some((ToolKind::Pickaxe, ToolTier::Wood)),
+            ItemType::StonePickaxe => Some((ToolKind::Pickaxe, ToolTier::Stone)),
+            ItemType::IronPickaxe => Some((ToolKind::Pickaxe, ToolTier::Iron)),
+            ItemType::WoodenAxe => Some((ToolKind::Axe, ToolTier::Wood)),
+            ItemType::StoneAxe => Some((ToolKind::Axe, ToolTier::Stone)),
+            ItemType::IronAxe => Some((ToolKind::Axe, ToolTier::Iron)),
+            ItemType::WoodenShovel => Some((ToolKind::Shovel, ToolTier::Wood)),
+            ItemType::StoneShovel => Some((ToolKind::Shovel, ToolTier::Stone)),
+            ItemType::IronShovel => Some((ToolKind::Shovel, ToolTier::Iron)),
+            ItemType::WoodenSword => Some((ToolKind::Sword, ToolTier::Wood)),
+            ItemType::StoneSword => Some((ToolKind::Sword, ToolTier::Stone)),
+            ItemType::IronSword => Some((ToolKind::Sword, ToolTier::Iron)),
+            _ => None,
+        }
+    }
+
+    /// Return the number of blocks the tool can mine before breaking. Return None if the item is not a tool.
+    pub const fn get_max_durability(&self) -> Option<u16> {
+        match self.get_tool() {
+            Some((_, tier)) => Some(tier.get_max_durability()),
+            None => None,
+        }
+    }
+
+    /// Return how many times faster the item mines a block of the given material
+    pub fn get_mining_speed(&self, material: BlockMaterial) -> f32 {
+        match self.get_tool() {
+            Some((kind, tier)) if kind.is_effective_on(material) => tier.get_mining_speed(),
+            _ => 1.,
+        }
+    }
+
+    /// Return the damage dealt when hitting an entity with the item, in half hearts
+    pub const fn get_attack_damage(&self) -> u8 {
+        match self.get_tool() {
+            Some((ToolKind::Sword, tier)) => 3 + tier as u8,
+            Some((ToolKind::Axe, tier)) => 2 + tier as u8,
+            Some(_) => 2,
+            None => 1,
+        }
+    }
+
+    /// Return how long the item burns in a furnace, in seconds. Return None if the item is not a fuel.
+    pub fn get_burn_duration(&self) -> Option<f32> {
+        match self {
+            ItemType::LogBlock
+            | ItemType::PlanksBlock
+            | ItemType::PlanksStairsBlock
+            | ItemType::FenceBlock
+            | ItemType::CraftingTableBlock
+            | ItemType::ChestBlock => Some(1.5 * world::SMELTING_DURATION),
+            ItemType::PlanksSlabBlock => Some(0.75 * world::SMELTING_DURATION),
+            ItemType::WoodenPickaxe
+            | ItemType::WoodenAxe
+            | ItemType::WoodenShovel
+            | ItemType::WoodenSword => Some(world::SMELTING_DURATION),
+            ItemType::SaplingBlock => Some(0.5 * world::SMELTING_DURATION),
+            ItemType::Stick => Some(0.25 * world::SMELTING_DURATION),
+            _ => None,
+        }
+    }
+}
+
+/// The icon of an item
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ItemTexture {
+    /// A tile of the block tileset
+    Tile(u8),
+    /// A sprite of the item atlas
+    Sprite(u8),
+}
+
+/// The tabs of the creative inventory
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CreativeCategory {
+    Building,
+    Nature,
+    Tools,
+    Redstone,
+}
+
+impl CreativeCategory {
+    pub const fn get_name(&self) -> &'static str {
+        match self {
+            CreativeCategory::Building => "Building",
+            CreativeCategory::Nature => "Nature",
+            CreativeCategory::Tools => "Tools",
+            CreativeCategory::Redstone => "Redstone",
+        }
+    }
+
+    /// Return the tab on the right of this one
+    pub const fn next(&self) -> Self {
+        match self {
+            CreativeCategory::Building => CreativeCategory::Nature,
+            CreativeCategory::Nature => CreativeCategory::Tools,
+            CreativeCategory::Tools => CreativeCategory::Redstone,
+            CreativeCategory::Redstone => CreativeCategory::Building,
+        }
+    }
+}
+
+/// The kind of a tool. Each kind mines faster the blocks of one material.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ToolKind {
+    Pickaxe,
+    Axe,
+    Shovel,
+    Sword,
+}
+
+impl ToolKind {
+    pub const fn is_effective_on(&self, material: BlockMaterial) -> bool {
+        matches!(
+            (self, material),
+            (ToolKind::Pickaxe, BlockMaterial::Rock)
+                | (ToolKind::Axe, BlockMaterial::Wood)
+                | (ToolKind::Shovel, BlockMaterial::Soil)
+                | (ToolKind::Sword, BlockMaterial::Plant)
+        )
+    }
+}
+
+/// The material a tool is made of. Higher tiers mine faster, last longer and harvest harder blocks.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum ToolTier {
+    Wood = 1,
+    Stone = 2,
+    Iron = 3,
+}
+
+impl ToolTier {
+    pub const fn get_mining_speed(&self) -> f32 {
+        match self {
+            ToolTier::Wood => 2.,
+            ToolTier::Stone => 4.,
+            ToolTier::Iron => 6.,
+        }
+    }
+
+    pub const fn get_max_durability(&self) -> u16 {
+        match self {
+            ToolTier::Wood => 60,
+            ToolTier::Stone => 132,
+            ToolTier::Iron => 251,
+        }
+    }
+}
+
+/// A set of items accepted by the same recipe ingredient, like any kind of planks
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+pub enum ItemGroup {
+    Planks = 0,
+    Logs = 1,
+}
+
+impl ItemGroup {
+    pub const fn get_from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(ItemGroup::Planks),
+            1 => Some(ItemGroup::Logs),
+            _ => None,
+        }
+    }
+
+    pub const fn contains(&self, item_type: ItemType) -> bool {
+        match self {
+            ItemGroup::Planks => matches!(item_type, ItemType::PlanksBlock),
+            ItemGroup::Logs => matches!(item_type, ItemType::LogBlock),
         }
     }
+
+    /// The item shown for the group in the recipe book
+    pub const fn get_icon(&self) -> ItemType {
+        match self {
+            ItemGroup::Planks => ItemType::PlanksBlock,
+            ItemGroup::Logs => ItemType::LogBlock,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BlockMaterial {
+    Rock,
+    Wood,
+    Soil,
+    Plant,
+    Other,
 }
 
 impl BlockType {
@@ -198,7 +838,68 @@ impl BlockType {
         *self == BlockType::Air
     }
 
-    pub fn get_texture_id(&self, dir: QuadDir) -> u8 {
+    pub const fn get_shape(&self) -> BlockShape {
+        match self {
+            BlockType::PlanksSlab | BlockType::CobblestoneSlab => BlockShape::Slab,
+            BlockType::PlanksStairs | BlockType::CobblestoneStairs => BlockShape::Stairs,
+            BlockType::Fence => BlockShape::Fence,
+            BlockType::Sapling | BlockType::Carrots => BlockShape::Plant,
+            BlockType::RedstoneWire => BlockShape::Wire,
+            BlockType::Lever => BlockShape::Lever,
+            BlockType::RedstoneTorch => BlockShape::Torch,
+            BlockType::Button => BlockShape::Button,
+            BlockType::Repeater => BlockShape::Repeater,
+            _ => BlockShape::Cube,
+        }
+    }
+
+    /// Return true if the block fills its entire space. Faces next to a full cube are hidden.
+    pub fn is_full_cube(&self) -> bool {
+        !self.is_air() && self.get_shape() == BlockShape::Cube
+    }
+
+    /// Return false if the entities can walk through the block
+    pub fn has_collision(&self) -> bool {
+        !self.is_air()
+            && !matches!(
+                self.get_shape(),
+                BlockShape::Plant
+                    | BlockShape::Wire
+                    | BlockShape::Lever
+                    | BlockShape::Torch
+                    | BlockShape::Button
+            )
+    }
+
+    /// Return true if the block emits, carries or receives redstone power
+    pub fn is_redstone_component(&self) -> bool {
+        matches!(
+            self,
+            BlockType::RedstoneWire
+                | BlockType::Lever
+                | BlockType::RedstoneTorch
+                | BlockType::Button
+                | BlockType::Repeater
+                | BlockType::RedstoneLamp
+        )
+    }
+
+    /// Return true if the block can be placed on top of the given block
+    pub fn can_be_placed_on(&self, block_below: BlockType) -> bool {
+        match self {
+            BlockType::Sapling | BlockType::Carrots => {
+                matches!(block_below, BlockType::Grass | BlockType::Dirt)
+            }
+            BlockType::RedstoneWire
+            | BlockType::Lever
+            | BlockType::RedstoneTorch
+            | BlockType::Button
+            | BlockType::Repeater => block_below.is_full_cube(),
+            _ => true,
+        }
+    }
+
+    pub fn get_texture_id(&self, dir: QuadDir, state: BlockState) -> u8 {
         match *self {
             BlockType::Air => 0,
             BlockType::Stone => 1,
@@ -213,9 +914,104 @@ impl BlockType {
             BlockType::Sand => 5,
             BlockType::Cobblestone => 6,
             BlockType::Border => 7,
-            BlockType::Log => 8,
+            BlockType::Log => {
+                // The rings are on the faces aligned with the log
+                let axis = state.get_facing().unwrap_or(QuadDir::Top);
+                if dir == axis || dir == axis.get_opposite() {
+                    16
+                } else {
+                    8
+                }
+            }
             BlockType::Leaves => 9,
             BlockType::Planks => 10,
+            BlockType::PlanksSlab => 10,
+            BlockType::CobblestoneSlab => 6,
+            BlockType::PlanksStairs => 10,
+            BlockType::CobblestoneStairs => 6,
+            BlockType::Fence => 10,
+            BlockType::Sapling => 17,
+            BlockType::Tnt => {
+                if dir == QuadDir::Top || dir == QuadDir::Bottom {
+                    19
+                } else {
+                    18
+                }
+            }
+            // The redstone components are brighter when they are powered
+            BlockType::RedstoneWire => match state.get_value() {
+                0 => 20,
+                1..8 => 64,
+                _ => 65,
+            },
+            BlockType::Lever => {
+                if state.get_value() > 0 {
+                    66
+                } else {
+                    21
+                }
+            }
+            BlockType::RedstoneTorch => {
+                if state.get_value() > 0 {
+                    22
+                } else {
+                    67
+                }
+            }
+            BlockType::Button => {
+                if state.get_value() > 0 {
+                    68
+                } else {
+                    23
+                }
+            }
+            BlockType::Repeater => {
+                if state.get_value() & REPEATER_POWERED_BIT != 0 {
+                    69
+                } else {
+                    24
+                }
+            }
+            BlockType::RedstoneLamp => {
+                if state.get_value() > 0 {
+                    70
+                } else {
+                    25
+                }
+            }
+            BlockType::Chest => {
+                if dir == QuadDir::Top || dir == QuadDir::Bottom {
+                    27
+                } else {
+                    26
+                }
+            }
+            BlockType::CraftingTable => {
+                if dir == QuadDir::Top {
+                    29
+                } else if dir == QuadDir::Bottom {
+                    10
+                } else {
+                    28
+                }
+            }
+            BlockType::Furnace => {
+                if dir == QuadDir::Top || dir == QuadDir::Bottom {
+                    31
+                } else if state.get_value() == LIT_FURNACE_VALUE {
+                    71
+                } else {
+                    30
+                }
+            }
+            BlockType::IronOre => 32,
+            BlockType::Carrots => {
+                if state.get_value() >= world::CROP_GROWTH_STAGES - 1 {
+                    34
+                } else {
+                    33
+                }
+            }
         }
     }
 
@@ -231,6 +1027,24 @@ impl BlockType {
             7 => Some(BlockType::Log),
             8 => Some(BlockType::Leaves),
             9 => Some(BlockType::Planks),
+            10 => Some(BlockType::PlanksSlab),
+            11 => Some(BlockType::CobblestoneSlab),
+            12 => Some(BlockType::PlanksStairs),
+            13 => Some(BlockType::CobblestoneStairs),
+            14 => Some(BlockType::Fence),
+            15 => Some(BlockType::Sapling),
+            16 => Some(BlockType::Tnt),
+            17 => Some(BlockType::RedstoneWire),
+            18 => Some(BlockType::Lever),
+            19 => Some(BlockType::RedstoneTorch),
+            20 => Some(BlockType::Button),
+            21 => Some(BlockType::Repeater),
+            22 => Some(BlockType::RedstoneLamp),
+            23 => Some(BlockType::Chest),
+            24 => Some(BlockType::CraftingTable),
+            25 => Some(BlockType::Furnace),
+            26 => Some(BlockType::IronOre),
+            27 => Some(BlockType::Carrots),
             _ => None,
         }
     }
@@ -247,9 +1061,88 @@ impl BlockType {
             BlockType::Log => 1.5,
             BlockType::Leaves => 0.3,
             BlockType::Planks => 1.2,
+            BlockType::PlanksSlab => 1.2,
+            BlockType::CobblestoneSlab => 2.2,
+            BlockType::PlanksStairs => 1.2,
+            BlockType::CobblestoneStairs => 2.2,
+            BlockType::Fence => 1.2,
+            BlockType::Sapling => 0.,
+            BlockType::Tnt => 0.,
+            BlockType::RedstoneWire => 0.,
+            BlockType::Lever => 0.5,
+            BlockType::RedstoneTorch => 0.,
+            BlockType::Button => 0.5,
+            BlockType::Repeater => 0.,
+            BlockType::RedstoneLamp => 0.3,
+            BlockType::Chest => 2.5,
+            BlockType::CraftingTable => 2.5,
+            BlockType::Furnace => 3.5,
+            BlockType::IronOre => 3.,
+            BlockType::Carrots => 0.,
+        }
+    }
+
+    /// Return the material of the block. Each tool mines faster one material.
+    pub const fn get_material(&self) -> BlockMaterial {
+        match self {
+            BlockType::Stone
+            | BlockType::Cobblestone
+            | BlockType::CobblestoneSlab
+            | BlockType::CobblestoneStairs
+            | BlockType::Furnace
+            | BlockType::IronOre => BlockMaterial::Rock,
+            BlockType::Log
+            | BlockType::Planks
+            | BlockType::PlanksSlab
+            | BlockType::PlanksStairs
+            | BlockType::Fence
+            | BlockType::Chest
+            | BlockType::CraftingTable => BlockMaterial::Wood,
+            BlockType::Grass | BlockType::Dirt | BlockType::Sand => BlockMaterial::Soil,
+            BlockType::Leaves | BlockType::Sapling | BlockType::Carrots => BlockMaterial::Plant,
+            _ => BlockMaterial::Other,
+        }
+    }
+
+    /// Return the minimal tier of the right tool needed for the block to drop an item.
+    /// Return None if the block can be harvested by hand.
+    pub const fn get_harvest_tier(&self) -> Option<ToolTier> {
+        match self {
+            BlockType::Stone
+            | BlockType::Cobblestone
+            | BlockType::CobblestoneSlab
+            | BlockType::CobblestoneStairs
+            | BlockType::Furnace => Some(ToolTier::Wood),
+            BlockType::IronOre => Some(ToolTier::Stone),
+            _ => None,
         }
     }
 
+    /// Return true if the block drops an item when it is mined with the given item
+    pub fn can_be_harvested_with(&self, item_type: ItemType) -> bool {
+        let Some(harvest_tier) = self.get_harvest_tier() else {
+            return true;
+        };
+        item_type.get_tool().is_some_and(|(kind, tier)| {
+            kind.is_effective_on(self.get_material()) && tier >= harvest_tier
+        })
+    }
+
+    /// Return the time needed to mine the block with the given item, in seconds
+    pub fn get_mining_time(&self, item_type: ItemType) -> f32 {
+        let time = self.get_hardness() / item_type.get_mining_speed(self.get_material());
+        if self.can_be_harvested_with(item_type) {
+            time
+        } else {
+            time * player::WRONG_TOOL_MINING_PENALTY
+        }
+    }
+
+    /// Return true if the block falls when there is nothing under it
+    pub const fn has_gravity(&self) -> bool {
+        matches!(self, BlockType::Sand)
+    }
+
     pub const fn get_dropped_item_type(&self) -> ItemType {
         match self {
             BlockType::Air => ItemType::Air,
@@ -262,6 +1155,85 @@ impl BlockType {
             BlockType::Log => ItemType::LogBlock,
             BlockType::Leaves => ItemType::LeavesBlock,
             BlockType::Planks => ItemType::PlanksBlock,
+            BlockType::PlanksSlab => ItemType::PlanksSlabBlock,
+            BlockType::CobblestoneSlab => ItemType::CobblestoneSlabBlock,
+            BlockType::PlanksStairs => ItemType::PlanksStairsBlock,
+            BlockType::CobblestoneStairs => ItemType::CobblestoneStairsBlock,
+            BlockType::Fence => ItemType::FenceBlock,
+            BlockType::Sapling => ItemType::SaplingBlock,
+            BlockType::Tnt => ItemType::TntBlock,
+            BlockType::RedstoneWire => ItemType::RedstoneWireBlock,
+            BlockType::Lever => ItemType::LeverBlock,
+            BlockType::RedstoneTorch => ItemType::RedstoneTorchBlock,
+            BlockType::Button => ItemType::ButtonBlock,
+            BlockType::Repeater => ItemType::RepeaterBlock,
+            BlockType::RedstoneLamp => ItemType::RedstoneLampBlock,
+            BlockType::Chest => ItemType::ChestBlock,
+            BlockType::CraftingTable => ItemType::CraftingTableBlock,
+            BlockType::Furnace => ItemType::FurnaceBlock,
+            BlockType::IronOre => ItemType::IronOreBlock,
+            BlockType::Carrots => ItemType::Carrot,
+        }
+    }
+
+    /// Return the number of items dropped by the block when it is broken
+    pub const fn get_dropped_amount(&self, state: BlockState) -> u8 {
+        match self {
+            BlockType::Carrots if state.get_value() >= world::CROP_GROWTH_STAGES - 1 => {
+                world::CARROTS_HARVEST_AMOUNT
+            }
+            _ => 1,
+        }
+    }
+
+    /// Return the resistance of the block to explosions. The higher it is, the closer to the explosion the block must be to be destroyed
+    pub const fn get_blast_resistance(&self) -> f32 {
+        match self {
+            BlockType::Air => 0.,
+            BlockType::Stone => 2.,
+            BlockType::Grass => 0.6,
+            BlockType::Dirt => 0.5,
+            BlockType::Sand => 0.5,
+            BlockType::Cobblestone => 2.,
+            BlockType::Border => f32::INFINITY,
+            BlockType::Log => 1.,
+            BlockType::Leaves => 0.2,
+            BlockType::Planks => 1.,
+            BlockType::PlanksSlab => 1.,
+            BlockType::CobblestoneSlab => 2.,
+            BlockType::PlanksStairs => 1.,
+            BlockType::CobblestoneStairs => 2.,
+            BlockType::Fence => 1.,
+            BlockType::Sapling => 0.,
+            BlockType::Tnt => 0.,
+            BlockType::RedstoneWire => 0.,
+            BlockType::Lever => 0.5,
+            BlockType::RedstoneTorch => 0.,
+            BlockType::Button => 0.5,
+            BlockType::Repeater => 0.,
+            BlockType::RedstoneLamp => 0.3,
+            BlockType::Chest => 2.5,
+            BlockType::CraftingTable => 2.5,
+            BlockType::Furnace => 3.5,
+            BlockType::IronOre => 3.,
+            BlockType::Carrots => 0.,
+        }
+    }
+
+    /// Return true if interacting with the block opens an interface
+    pub fn has_ui(&self) -> bool {
+        matches!(
+            self,
+            BlockType::Chest | BlockType::CraftingTable | BlockType::Furnace
+        )
+    }
+
+    /// Return the number of slots of the inventory bound to the block, if it has one
+    pub const fn get_inventory_size(&self) -> Option<usize> {
+        match self {
+            BlockType::Chest => Some(world::CHEST_INVENTORY_SIZE),
+            BlockType::Furnace => Some(world::FURNACE_INVENTORY_SIZE),
+            _ => None,
         }
     }
 }
@@ -277,6 +1249,35 @@ pub fn get_quad_color_from_texture_id(id: u8) -> Color565 {
         8 => Color565::from_rgb888(79, 53, 30),
         9 => Color565::from_rgb888(36, 75, 37),
         10 => Color565::from_rgb888(152, 124, 61),
+        16 => Color565::from_rgb888(143, 109, 66),
+        17 => Color565::from_rgb888(52, 120, 30),
+        18 => Color565::from_rgb888(196, 44, 36),
+        19 => Color565::from_rgb888(140, 130, 120),
+        20 => Color565::from_rgb888(90, 10, 10),
+        21 => Color565::from_rgb888(120, 90, 60),
+        22 => Color565::from_rgb888(255, 60, 30),
+        23 => Color565::from_rgb888(150, 150, 150),
+        24 => Color565::from_rgb888(170, 160, 160),
+        25 => Color565::from_rgb888(120, 80, 40),
+        26 => Color565::from_rgb888(155, 105, 45),
+        27 => Color565::from_rgb888(125, 85, 35),
+        28 => Color565::from_rgb888(140, 100, 55),
+        29 => Color565::from_rgb888(175, 130, 75),
+        30 => Color565::from_rgb888(110, 110, 110),
+        31 => Color565::from_rgb888(135, 135, 135),
+        32 => Color565::from_rgb888(150, 140, 130),
+        33 => Color565::from_rgb888(60, 110, 35),
+        34 => Color565::from_rgb888(170, 120, 40),
+
+        // Colors of the powered redstone components. They have no tile in the tileset.
+        64 => Color565::from_rgb888(170, 20, 20),
+        65 => Color565::from_rgb888(255, 40, 30),
+        66 => Color565::from_rgb888(200, 60, 40),
+        67 => Color565::from_rgb888(100, 40, 30),
+        68 => Color565::from_rgb888(110, 110, 110),
+        69 => Color565::from_rgb888(220, 120, 110),
+        70 => Color565::from_rgb888(255, 220, 120),
+        71 => Color565::from_rgb888(230, 140, 50),
 
         _ => Color565::from_rgb888(0, 0, 0),
         // 255 is reserved for block outline
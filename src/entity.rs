@@ -1,12 +1,31 @@
-use core::any::Any;
-
 use nalgebra::Vector3;
+use serde::{Deserialize, Serialize};
 
-use crate::{constants::EntityType, physic::BoundingBox};
-
-calc_use!(alloc::boxed::Box);
+use crate::{
+    constants::{EntityType, world::HURT_DURATION},
+    entity::{
+        falling_block::FallingBlockData, item::ItemEntityData, mob::MobData,
+        primed_tnt::PrimedTntData,
+    },
+    physic::BoundingBox,
+};
 
+pub mod falling_block;
 pub mod item;
+pub mod mob;
+pub mod pig;
+pub mod primed_tnt;
+pub mod zombie;
+
+/// The data specific to the type of an entity
+#[derive(Serialize, Deserialize)]
+pub enum EntityData {
+    None,
+    Item(ItemEntityData),
+    FallingBlock(FallingBlockData),
+    PrimedTnt(PrimedTntData),
+    Mob(MobData),
+}
 
 pub struct Entity {
     id: usize,
@@ -16,11 +35,17 @@ pub struct Entity {
     pub rotation: Vector3<f32>,
     pub velocity: Vector3<f32>,
     pub is_on_floor: bool,
-    pub custom_data: Option<Box<dyn Any>>,
+    /// Health points, in half hearts. None if the entity can't take damage.
+    pub health: Option<u8>,
+    /// Time left since the entity was hurt, in seconds
+    pub hurt_timer: f32,
+    pub data: EntityData,
+    /// Set when the entity must be removed from the world at the end of the update
+    pub removed: bool,
 }
 
 impl Entity {
-    pub fn new(id: usize, entity_type: EntityType, custom_data: Option<Box<dyn Any>>) -> Self {
+    pub fn new(id: usize, entity_type: EntityType, data: EntityData) -> Self {
         Entity {
             id,
             entity_type,
@@ -29,7 +54,10 @@ impl Entity {
             pos: Vector3::zeros(),
             rotation: Vector3::zeros(),
             is_on_floor: false,
-            custom_data,
+            health: entity_type.get_max_health(),
+            hurt_timer: 0.,
+            data,
+            removed: false,
         }
     }
 
@@ -44,4 +72,34 @@ impl Entity {
     pub fn get_bbox(&self) -> Option<BoundingBox> {
         Some(self.entity_type.get_bbox()?.transform(self.pos))
     }
+
+    /// Give back health points to the entity, up to its max health
+    pub fn heal(&mut self, amount: u8) {
+        if let Some(health) = &mut self.health
+            && let Some(max_health) = self.entity_type.get_max_health()
+        {
+            *health = health.saturating_add(amount).min(max_health);
+        }
+    }
+
+    /// Remove health points from the entity. Does nothing if the entity can't take damage.
+    pub fn damage(&mut self, amount: u8) {
+        if let Some(health) = &mut self.health {
+            *health = health.saturating_sub(amount);
+            self.hurt_timer = HURT_DURATION;
+        }
+    }
+
+    /// Damage the entity and push it with the given velocity. Does nothing if the entity can't take damage.
+    pub fn hit(&mut self, amount: u8, knockback: Vector3<f32>) {
+        if self.health.is_some() {
+            self.damage(amount);
+            self.velocity += knockback;
+        }
+    }
+
+    /// Return true while the entity is recovering from a hit
+    pub fn is_hurt(&self) -> bool {
+        self.hurt_timer > 0.
+    }
 }
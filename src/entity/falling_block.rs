@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::BlockType,
+    entity::{Entity, EntityData},
+    world::block_state::BlockState,
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct FallingBlockData {
+    pub block_type: BlockType,
+    pub block_state: BlockState,
+}
+
+impl FallingBlockData {
+    pub fn get_falling_block_data(entity: &Entity) -> Option<&Self> {
+        match &entity.data {
+            EntityData::FallingBlock(falling_block_data) => Some(falling_block_data),
+            _ => None,
+        }
+    }
+}
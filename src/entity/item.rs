@@ -1,16 +1,34 @@
-use crate::{entity::Entity, inventory::ItemStack};
+use serde::{Deserialize, Serialize};
 
-pub struct ItemEntityCustomData {
+use crate::{
+    entity::{Entity, EntityData},
+    inventory::ItemStack,
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct ItemEntityData {
     pub item_stack: ItemStack,
+    /// Time before the item can be picked up, in seconds
+    pub pickup_delay: f32,
 }
 
-impl ItemEntityCustomData {
+impl ItemEntityData {
     pub fn get_item_data(entity: &Entity) -> Option<&Self> {
-        let custom_data = entity.custom_data.as_ref()?;
-        let item_data = custom_data
-            .downcast_ref::<ItemEntityCustomData>()
-            .expect("Item Entity custom data must be an instance of struct ItemEntityCustomData.");
+        match &entity.data {
+            EntityData::Item(item_data) => Some(item_data),
+            _ => None,
+        }
+    }
+
+    pub fn get_item_data_mut(entity: &mut Entity) -> Option<&mut Self> {
+        match &mut entity.data {
+            EntityData::Item(item_data) => Some(item_data),
+            _ => None,
+        }
+    }
 
-        Some(item_data)
+    /// Return true if the item can be picked up by the player
+    pub fn can_be_picked_up(entity: &Entity) -> bool {
+        Self::get_item_data(entity).is_some_and(|item_data| item_data.pickup_delay <= 0.)
     }
 }
@@ -0,0 +1,323 @@
+use libm::{atan2f, floorf, sincosf};
+use nalgebra::{Vector2, Vector3};
+use rand_xorshift::XorShiftRng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::{
+        EntityType, ItemTexture, ItemType,
+        mob::{
+            FLEE_DISTANCE, JUMP_FORCE, MAX_WANDER_DURATION, MIN_WANDER_DURATION, PANIC_DURATION,
+            REPATH_INTERVAL, WALK_FORCE, WANDER_IDLE_CHANCE, WAYPOINT_REACHED_DISTANCE,
+        },
+    },
+    entity::{Entity, EntityData, pig::Pig, zombie::Zombie},
+    misc::random_f32,
+    physic::BoundingBox,
+    world::{
+        chunk_manager::ChunksManager,
+        pathfinding::{PathSearch, PathSize},
+    },
+};
+
+calc_use!(alloc::vec::Vec);
+
+/// The behaviour of a kind of mob. Each mob entity type has one.
+pub trait MobBehavior {
+    /// Return the sprite drawn at the position of the mob
+    fn get_texture(&self) -> ItemTexture;
+
+    /// Return true if the mob attacks the player. Hostile mobs spawn in the dark.
+    fn is_hostile(&self) -> bool;
+
+    /// Return the damage dealt to the player touching the mob, in half hearts
+    fn get_contact_damage(&self) -> u8;
+
+    /// Return true if the mob can spawn with its feet in the block at the given position
+    fn can_spawn_at(&self, chunks_manager: &ChunksManager, pos: Vector3<isize>) -> bool;
+
+    /// Decide where the mob goes. Called every frame, before the physic engine moves it.
+    fn update(&self, entity: &mut Entity, context: &mut MobContext, delta_time: f32);
+
+    /// Return the item dropped when the mob is killed and its maximum amount
+    fn get_drop(&self) -> (ItemType, u8);
+}
+
+impl EntityType {
+    /// Return the behaviour of the mob. Return None if the entity is not a mob.
+    pub fn get_mob_behavior(&self) -> Option<&'static dyn MobBehavior> {
+        match self {
+            EntityType::Pig => Some(&Pig),
+            EntityType::Zombie => Some(&Zombie),
+            _ => None,
+        }
+    }
+}
+
+/// What the mobs know about the world when they update
+pub struct MobContext<'a> {
+    pub chunks_manager: &'a ChunksManager,
+    pub player: &'a Entity,
+    pub rng: &'a mut XorShiftRng,
+    /// Path search nodes the mobs can still explore during this frame
+    pub pathfinding_budget: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct MobData {
+    /// Angle around the Y axis the mob is walking to. None if it stands still.
+    pub wander_direction: Option<f32>,
+    /// Time before the mob picks a new direction, in seconds
+    pub wander_timer: f32,
+    /// The path is searched again after loading the world
+    #[serde(skip, default = "Navigation::new")]
+    pub navigation: Navigation,
+    /// Health during the last update, to notice when the mob gets hurt
+    pub last_health: Option<u8>,
+    /// Time the mob keeps fleeing after being hurt, in seconds
+    pub panic_timer: f32,
+    /// Time before the mob can hurt the player again, in seconds
+    pub attack_cooldown: f32,
+}
+
+impl MobData {
+    pub fn new() -> Self {
+        MobData {
+            wander_direction: None,
+            wander_timer: 0.,
+            navigation: Navigation::new(),
+            last_health: None,
+            panic_timer: 0.,
+            attack_cooldown: 0.,
+        }
+    }
+
+    pub fn get_mob_data_mut(entity: &mut Entity) -> Option<&mut Self> {
+        match &mut entity.data {
+            EntityData::Mob(mob_data) => Some(mob_data),
+            _ => None,
+        }
+    }
+}
+
+/// The path a mob is following and the search of the next one
+pub struct Navigation {
+    search: Option<PathSearch>,
+    /// The remaining blocks of the path, the next one at the end
+    path: Vec<Vector3<isize>>,
+    goal: Option<Vector3<isize>>,
+    size: Option<PathSize>,
+    /// Time before the path can be searched again, in seconds
+    repath_timer: f32,
+}
+
+impl Navigation {
+    pub fn new() -> Self {
+        Navigation {
+            search: None,
+            path: Vec::new(),
+            goal: None,
+            size: None,
+            repath_timer: 0.,
+        }
+    }
+
+    /// Forget the current path and goal
+    pub fn stop(&mut self) {
+        self.search = None;
+        self.path.clear();
+        self.goal = None;
+    }
+
+    /// Search a path to the goal and return the horizontal direction to the next block of the path.
+    /// The path is searched again when the goal changes or when the mob is lost, at most every REPATH_INTERVAL.
+    pub fn update(
+        &mut self,
+        bbox: &BoundingBox,
+        goal: Vector3<isize>,
+        context: &mut MobContext,
+        delta_time: f32,
+    ) -> Option<Vector2<f32>> {
+        let size = PathSize::from_bbox(bbox);
+        let start = size.get_start_pos(bbox);
+
+        self.repath_timer -= delta_time;
+        let is_lost = self.path.is_empty() && start != goal;
+        if self.repath_timer <= 0. && self.search.is_none() && (self.goal != Some(goal) || is_lost)
+        {
+            self.search = Some(PathSearch::new(start, goal, size));
+            self.goal = Some(goal);
+            self.size = Some(size);
+            self.repath_timer = REPATH_INTERVAL;
+        }
+
+        // Keep following the previous path while the new one is searched
+        if let Some(search) = &mut self.search {
+            search.step(context.chunks_manager, &mut context.pathfinding_budget);
+            if let Some(mut path) = search.take_result() {
+                path.reverse();
+                self.path = path;
+                self.search = None;
+            }
+        }
+
+        let size = self.size?;
+        let center = bbox.offset + bbox.size / 2.;
+        while let Some(next) = self.path.last() {
+            let offset = size.get_block_center(*next).xz() - center.xz();
+            if offset.norm() < WAYPOINT_REACHED_DISTANCE {
+                // Wait to land after a drop
+                if (next.y - start.y).abs() > 1 {
+                    return None;
+                }
+                self.path.pop();
+                continue;
+            }
+            return Some(offset.normalize());
+        }
+        None
+    }
+}
+
+/// Walk to the given position by following a path. Return false if the mob doesn't know how to get closer.
+pub fn follow(
+    entity: &mut Entity,
+    context: &mut MobContext,
+    target: Vector3<f32>,
+    speed: f32,
+    delta_time: f32,
+) -> bool {
+    let Some(bbox) = entity.get_bbox() else {
+        return false;
+    };
+    let Some(mob_data) = MobData::get_mob_data_mut(entity) else {
+        return false;
+    };
+
+    let goal = target.map(|v| floorf(v) as isize);
+    let Some(direction) = mob_data.navigation.update(&bbox, goal, context, delta_time) else {
+        return false;
+    };
+
+    entity.rotation.y = atan2f(direction.x, direction.y);
+    walk(entity, context.chunks_manager, direction, speed, delta_time);
+    true
+}
+
+/// Run away from the given position by following a path
+pub fn flee(
+    entity: &mut Entity,
+    context: &mut MobContext,
+    danger: Vector3<f32>,
+    speed: f32,
+    delta_time: f32,
+) {
+    let mut away = (entity.pos - danger).xz();
+    if away.norm() == 0. {
+        away = Vector2::new(1., 0.);
+    }
+    let target = entity.pos + Vector3::new(away.x, 0., away.y).normalize() * FLEE_DISTANCE;
+    follow(entity, context, target, speed, delta_time);
+}
+
+/// Return true while the mob panics after being hurt
+pub fn update_panic(entity: &mut Entity, delta_time: f32) -> bool {
+    let health = entity.health;
+    let Some(mob_data) = MobData::get_mob_data_mut(entity) else {
+        return false;
+    };
+
+    if health < mob_data.last_health {
+        mob_data.panic_timer = PANIC_DURATION;
+    }
+    mob_data.last_health = health;
+
+    mob_data.panic_timer -= delta_time;
+    if mob_data.panic_timer <= 0. {
+        mob_data.navigation.stop();
+        return false;
+    }
+    true
+}
+
+/// Walk in a random direction or stand still, changing from time to time
+pub fn wander(entity: &mut Entity, context: &mut MobContext, speed: f32, delta_time: f32) {
+    let Some(mob_data) = MobData::get_mob_data_mut(entity) else {
+        return;
+    };
+
+    mob_data.wander_timer -= delta_time;
+    if mob_data.wander_timer <= 0. {
+        mob_data.wander_timer = MIN_WANDER_DURATION
+            + (MAX_WANDER_DURATION - MIN_WANDER_DURATION) * random_f32(context.rng);
+        mob_data.wander_direction = if random_f32(context.rng) < WANDER_IDLE_CHANCE {
+            None
+        } else {
+            Some(random_f32(context.rng) * 2. * core::f32::consts::PI)
+        };
+    }
+
+    if let Some(angle) = mob_data.wander_direction {
+        entity.rotation.y = angle;
+        let (x, z) = sincosf(angle);
+        walk(
+            entity,
+            context.chunks_manager,
+            Vector2::new(x, z),
+            speed,
+            delta_time,
+        );
+    }
+}
+
+/// Push the mob in the given horizontal direction and jump on the single blocks in its way
+pub fn walk(
+    entity: &mut Entity,
+    chunks_manager: &ChunksManager,
+    direction: Vector2<f32>,
+    speed: f32,
+    delta_time: f32,
+) {
+    entity.velocity.x += direction.x * WALK_FORCE * delta_time;
+    entity.velocity.z += direction.y * WALK_FORCE * delta_time;
+
+    // Limit speed
+    if entity.velocity.xz().norm() > speed {
+        let max_velocity = entity.velocity.xz().normalize() * speed;
+        entity.velocity.x = max_velocity.x;
+        entity.velocity.z = max_velocity.y;
+    }
+
+    if entity.is_on_floor && can_jump_over(entity, chunks_manager, direction) {
+        entity.velocity.y = JUMP_FORCE;
+    }
+}
+
+/// Return true if the block in front of the mob feet is solid and the mob fits above it
+fn can_jump_over(entity: &Entity, chunks_manager: &ChunksManager, direction: Vector2<f32>) -> bool {
+    let Some(bbox) = entity.get_bbox() else {
+        return false;
+    };
+
+    // Look a bit further than the edge of the bounding box
+    let reach = bbox.size.x / 2. + 0.2;
+    let ahead = Vector3::new(
+        floorf(entity.pos.x + direction.x * reach) as isize,
+        floorf(bbox.offset.y + 0.1) as isize,
+        floorf(entity.pos.z + direction.y * reach) as isize,
+    );
+
+    if !chunks_manager
+        .get_block_in_world(ahead)
+        .is_some_and(|block| block.has_collision())
+    {
+        return false;
+    }
+
+    let height = bbox.size.y as isize + 1;
+    (1..=height).all(|y| {
+        chunks_manager
+            .get_block_in_world(ahead + Vector3::new(0, y, 0))
+            .is_some_and(|block| !block.has_collision())
+    })
+}
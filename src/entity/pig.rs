@@ -0,0 +1,52 @@
+use nalgebra::Vector3;
+
+use crate::{
+    constants::{
+        BlockType, ItemTexture, ItemType,
+        mob::{PASSIVE_MIN_SPAWN_LIGHT, PIG_MAX_DROPS, PIG_PANIC_SPEED, PIG_SPEED},
+    },
+    entity::{
+        Entity,
+        mob::{MobBehavior, MobContext, flee, update_panic, wander},
+    },
+    world::chunk_manager::ChunksManager,
+};
+
+/// A passive animal wandering on the grass
+pub struct Pig;
+
+impl MobBehavior for Pig {
+    fn get_texture(&self) -> ItemTexture {
+        ItemTexture::Sprite(17)
+    }
+
+    fn is_hostile(&self) -> bool {
+        false
+    }
+
+    fn get_contact_damage(&self) -> u8 {
+        0
+    }
+
+    fn can_spawn_at(&self, chunks_manager: &ChunksManager, pos: Vector3<isize>) -> bool {
+        chunks_manager.get_block_in_world(pos - Vector3::new(0, 1, 0)) == Some(BlockType::Grass)
+            && chunks_manager
+                .get_block_in_world(pos)
+                .is_some_and(|block| !block.has_collision())
+            && chunks_manager.get_sky_light(pos) >= PASSIVE_MIN_SPAWN_LIGHT
+    }
+
+    fn update(&self, entity: &mut Entity, context: &mut MobContext, delta_time: f32) {
+        // Run away from the player after being hurt
+        if update_panic(entity, delta_time) {
+            let danger = context.player.pos;
+            flee(entity, context, danger, PIG_PANIC_SPEED, delta_time);
+        } else {
+            wander(entity, context, PIG_SPEED, delta_time);
+        }
+    }
+
+    fn get_drop(&self) -> (ItemType, u8) {
+        (ItemType::Porkchop, PIG_MAX_DROPS)
+    }
+}
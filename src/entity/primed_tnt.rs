@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+use crate::entity::{Entity, EntityData};
+
+#[derive(Serialize, Deserialize)]
+pub struct PrimedTntData {
+    pub fuse: f32, // Remaining time before the explosion in seconds
+}
+
+impl PrimedTntData {
+    pub fn get_primed_tnt_data_mut(entity: &mut Entity) -> Option<&mut Self> {
+        match &mut entity.data {
+            EntityData::PrimedTnt(primed_tnt_data) => Some(primed_tnt_data),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use nalgebra::Vector3;
+
+use crate::{
+    constants::{
+        BlockType, ItemTexture, ItemType,
+        mob::{
+            HOSTILE_MAX_SPAWN_LIGHT, ZOMBIE_ATTACK_DAMAGE, ZOMBIE_DETECTION_DISTANCE,
+            ZOMBIE_MAX_DROPS, ZOMBIE_SPEED,
+        },
+    },
+    entity::{
+        Entity,
+        mob::{MobBehavior, MobContext, follow, walk, wander},
+    },
+    world::chunk_manager::ChunksManager,
+};
+
+/// A hostile mob spawning in the dark and chasing the player
+pub struct Zombie;
+
+impl MobBehavior for Zombie {
+    fn get_texture(&self) -> ItemTexture {
+        ItemTexture::Sprite(18)
+    }
+
+    fn is_hostile(&self) -> bool {
+        true
+    }
+
+    fn get_contact_damage(&self) -> u8 {
+        ZOMBIE_ATTACK_DAMAGE
+    }
+
+    fn can_spawn_at(&self, chunks_manager: &ChunksManager, pos: Vector3<isize>) -> bool {
+        // Only on natural ground, so that the player buildings stay safe
+        chunks_manager
+            .get_block_in_world(pos - Vector3::new(0, 1, 0))
+            .is_some_and(|block| {
+                matches!(
+                    block,
+                    BlockType::Grass | BlockType::Dirt | BlockType::Stone | BlockType::Sand
+                )
+            })
+            && (0..2).all(|y| {
+                chunks_manager
+                    .get_block_in_world(pos + Vector3::new(0, y, 0))
+                    .is_some_and(|block| !block.has_collision())
+            })
+            && chunks_manager.get_sky_light(pos) <= HOSTILE_MAX_SPAWN_LIGHT
+    }
+
+    fn update(&self, entity: &mut Entity, context: &mut MobContext, delta_time: f32) {
+        // Players that can't take damage are ignored
+        let player_pos = context.player.pos;
+        if context.player.health.is_none()
+            || entity.pos.metric_distance(&player_pos) > ZOMBIE_DETECTION_DISTANCE
+        {
+            wander(entity, context, ZOMBIE_SPEED / 2., delta_time);
+            return;
+        }
+
+        // Go straight to the player once the end of the path is reached
+        if !follow(entity, context, player_pos, ZOMBIE_SPEED, delta_time) {
+            let direction = (player_pos - entity.pos).xz();
+            if direction.norm() > 0. {
+                walk(
+                    entity,
+                    context.chunks_manager,
+                    direction.normalize(),
+                    ZOMBIE_SPEED,
+                    delta_time,
+                );
+            }
+        }
+    }
+
+    fn get_drop(&self) -> (ItemType, u8) {
+        (ItemType::RottenFlesh, ZOMBIE_MAX_DROPS)
+    }
+}
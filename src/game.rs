@@ -9,6 +9,7 @@ use serde::{Deserialize, Serialize};
 use crate::{
     constants::{
         color_palette::MENU_BACKGROUND_COLOR,
+        player::MAX_HEALTH,
         rendering::{MAX_FOV, MAX_RENDER_DISTANCE, MIN_FOV},
     },
     game::{crafting_manager::CraftingManager, game_menus::SettingsMenu},
@@ -82,13 +83,17 @@ impl Game {
                 16.5,
             );
 
+            self.save_manager.set_spawn_pos(player_spawn_pos);
+
             self.player.inventory.fill(ItemStack::void());
+            self.player.restore_hunger();
 
             self.world.get_player_entity_mut().pos = player_spawn_pos;
             let player_entity = self.world.get_player_entity_mut();
             player_entity.pos = player_spawn_pos;
             player_entity.rotation = Vector3::repeat(0.0);
             player_entity.velocity = Vector3::repeat(0.0);
+            player_entity.health = Some(MAX_HEALTH);
             self.renderer.camera.set_rotation(player_entity.rotation);
 
             self.hud.sync(&self.player);
@@ -125,10 +130,17 @@ impl Game {
             player_entity.pos = self.save_manager.get_player_pos();
             player_entity.rotation = self.save_manager.get_player_rot();
             player_entity.velocity = Vector3::repeat(0.0);
+            player_entity.health = Some(self.save_manager.get_player_health());
             self.renderer.camera.set_rotation(player_entity.rotation);
 
             self.player
                 .set_inventory(self.save_manager.get_player_inventory());
+            self.player.hunger = self.save_manager.get_player_hunger();
+
+            self.world
+                .set_registered_inventories(self.save_manager.get_registered_inventories());
+            self.world.set_furnaces(self.save_manager.get_furnaces());
+            self.save_manager.spawn_entities(&mut self.world);
 
             // Load world info
             let world_info = self.save_manager.get_current_loaded_world_info();
@@ -137,10 +149,13 @@ impl Game {
 
         self.save_manager.clean(); // Clear save manager to save memory
 
+        // The player can't take damage in creative
+        let player_entity = self.world.get_player_entity_mut();
         if self.save_manager.get_game_mode() == GameMode::Creative {
-            self.world.get_player_entity_mut().gravity = false;
+            player_entity.gravity = false;
+            player_entity.health = None;
         } else {
-            self.world.get_player_entity_mut().gravity = true;
+            player_entity.gravity = true;
         }
 
         // Show a warning message
@@ -183,9 +198,18 @@ impl Game {
                 self.timing_manager.get_delta_time(),
                 &self.settings,
             );
-            self.hud.update(&self.input_manager, &self.player);
+            self.hud
+                .update(&self.input_manager, &self.player, &self.world);
             self.hud.sync(&self.player);
 
+            if self.world.get_player_entity().health == Some(0) {
+                return GameState::PlayerDied;
+            }
+
+            if let Some(pos) = self.player.opened_block_ui.take() {
+                return GameState::OpenBlockUI(pos);
+            }
+
             self.renderer.camera.update(
                 self.timing_manager.get_delta_time(),
                 &self.input_manager,
@@ -195,6 +219,12 @@ impl Game {
             self.world.chunks_manager.check_mesh_regeneration();
             self.world
                 .update_entities(self.timing_manager.get_delta_time());
+            self.world
+                .update_random_ticks(self.timing_manager.get_delta_time());
+            self.world
+                .update_blocks(self.timing_manager.get_delta_time());
+            self.world
+                .update_furnaces(self.timing_manager.get_delta_time());
             self.physic_engine
                 .process(&mut self.world, self.timing_manager.get_delta_time());
 
@@ -213,10 +243,22 @@ impl Game {
         self.renderer.enable_vsync = self.settings.vsync;
     }
 
+    /// Bring the player back to the world spawn with full health and hunger
+    fn respawn_player(&mut self) {
+        self.player.restore_hunger();
+
+        let player_entity = self.world.get_player_entity_mut();
+        player_entity.pos = self.save_manager.get_spawn_pos();
+        player_entity.velocity = Vector3::repeat(0.0);
+        player_entity.health = Some(MAX_HEALTH);
+    }
+
     fn exit_world(&mut self) {
         for chunk in self.world.chunks_manager.chunks.iter() {
             self.save_manager.set_chunk(chunk);
         }
+        self.save_manager.update_block_entities(&self.world);
+        self.save_manager.update_entities(&self.world);
         self.world.clear();
 
         self.save_manager
@@ -243,6 +285,8 @@ impl Game {
                 GameState::DeleteWorld(filename) => self.delete_world_menu_loop(&filename),
                 GameState::CreateWorld(file_name) => self.create_world_menu_loop(&file_name),
                 GameState::OpenPlayerInventory(page) => self.player_inventory_loop(page),
+                GameState::OpenBlockUI(pos) => self.block_ui_loop(pos),
+                GameState::PlayerDied => self.death_screen_loop(),
                 GameState::Quit => break,
             }
         }
@@ -255,6 +299,8 @@ pub enum GameState {
     GoSelectWorld,
     InGame,
     OpenPlayerInventory(game_uis::PlayerInventoryPage),
+    OpenBlockUI(Vector3<isize>),
+    PlayerDied,
     LoadWorld(String, bool), // String: filename, String: world name
     CreateWorld(String),     // String: file_name
     DeleteWorld(String),
@@ -1,98 +1,145 @@
 use crate::{
-    constants::ItemType,
+    constants::{ItemGroup, ItemType},
     inventory::{Inventory, ItemStack},
+    world::World,
 };
 
+/// What a slot of a craft accepts
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Ingredient {
+    None,
+    Item(ItemType),
+    Group(ItemGroup),
+}
+
+#[derive(Clone, Copy, Debug)]
+struct CraftSlot {
+    ingredient: Ingredient,
+    /// The number of items consumed by the craft
+    amount: u8,
+    /// The item left in the slot once the craft is done, like an empty bucket. Air if there is none.
+    leftover: ItemType,
+}
+
+impl CraftSlot {
+    const EMPTY: Self = CraftSlot {
+        ingredient: Ingredient::None,
+        amount: 0,
+        leftover: ItemType::Air,
+    };
+
+    pub const fn new(data: &'static [u8]) -> Self {
+        // 1 byte : kind of ingredient. 0 for none, 1 for an item and 2 for a group
+        // 1 byte : item or group id
+        let ingredient = match data[0] {
+            0 => Ingredient::None,
+            1 => {
+                Ingredient::Item(ItemType::get_from_id(data[1]).expect("Invalid item id in craft."))
+            }
+            2 => Ingredient::Group(
+                ItemGroup::get_from_id(data[1]).expect("Invalid item group id in craft."),
+            ),
+            _ => panic!("Invalid ingredient kind in craft."),
+        };
+
+        // 1 byte : amount
+        // 1 byte : leftover
+        CraftSlot {
+            ingredient,
+            amount: data[2],
+            leftover: ItemType::get_from_id(data[3]).expect("Invalid item id in craft."),
+        }
+    }
+
+    /// Return true if the content of a grid slot can be used for this slot of the craft
+    fn accepts(&self, (item_type, amount): (ItemType, u8)) -> bool {
+        match self.ingredient {
+            Ingredient::None => item_type == ItemType::Air,
+            Ingredient::Item(ingredient_type) => {
+                item_type == ingredient_type && amount >= self.amount
+            }
+            Ingredient::Group(group) => group.contains(item_type) && amount >= self.amount,
+        }
+    }
+}
+
+/// The content of the crafting grid: the item type and the amount of each slot
+type CraftingGrid = [[(ItemType, u8); 3]; 3];
+
+/// The slot of the craft used by each slot of the grid
+type Consumption = [[CraftSlot; 3]; 3];
+
 struct Craft {
-    pattern: [[ItemType; 3]; 3],
-    strict_shape: bool,
+    pattern: [[CraftSlot; 3]; 3],
+    /// A shaped craft can be placed anywhere in the grid but must keep its shape. Otherwise, any arrangement of the ingredients matches.
+    shaped: bool,
     result: ItemStack,
 }
 
 impl Craft {
     pub const fn new(data: &'static [u8]) -> Self {
-        let mut pattern = [const { [ItemType::Air; 3] }; 3];
+        let mut pattern = [[CraftSlot::EMPTY; 3]; 3];
 
-        // 9 bytes : pattern
+        // 9 * 4 bytes : pattern
         let mut index = 0;
 
         // We can't use a for loop because it is not available in const context
         while index < 9 {
-            pattern[index % 3][index / 3] =
-                ItemType::get_from_id(data[index]).expect("Invalid item id in craft.");
+            let (_, slot_data) = data.split_at(index * 4);
+            pattern[index % 3][index / 3] = CraftSlot::new(slot_data);
             index += 1;
         }
 
-        // 1 byte : strict_shape
-        let strict_shape = data[9] == 1;
+        // 1 byte : shaped
+        let shaped = data[36] == 1;
 
         // 1 byte : result
-        let result_type = ItemType::get_from_id(data[10]).expect("Invalid item id in craft.");
+        let result_type = ItemType::get_from_id(data[37]).expect("Invalid item id in craft.");
 
         // 1 byte : result amount
-        let result_amount = data[11];
+        let result_amount = data[38];
 
         let result = ItemStack::new(result_type, result_amount, false);
 
         Craft {
             pattern,
-            strict_shape,
+            shaped,
             result,
         }
     }
 
-    pub fn matches(&self, grid: [[ItemType; 3]; 3]) -> bool {
-        if self.strict_shape {
+    /// Return what must be taken from each slot of the grid if the grid matches the craft
+    pub fn get_consumption(&self, grid: &CraftingGrid) -> Option<Consumption> {
+        let is_empty = |x: usize, y: usize| grid[x][y].0 == ItemType::Air;
+        let mut consumption = [[CraftSlot::EMPTY; 3]; 3];
+
+        if self.shaped {
             // Get the tiniest possible rectangle in the grid
 
             // Get the top left corner x
             let mut x1 = 0;
-            'scan: while x1 < 3 {
-                for i in 0..3 {
-                    if grid[x1][i] != ItemType::Air {
-                        break 'scan;
-                    }
-                }
+            while x1 < 3 && (0..3).all(|i| is_empty(x1, i)) {
                 x1 += 1;
             }
             if x1 == 3 {
-                return false;
+                return None;
             }
 
             // Get the top left corner y
             let mut y1 = 0;
-            'scan: while y1 < 3 {
-                for i in 0..3 {
-                    if grid[i][y1] != ItemType::Air {
-                        break 'scan;
-                    }
-                }
+            while y1 < 3 && (0..3).all(|i| is_empty(i, y1)) {
                 y1 += 1;
             }
 
-            if y1 == 3 {
-                return false;
-            }
-
             // Get the bottom right corner x
             let mut x2 = 2;
-            'scan: while x2 > x1 {
-                for i in 0..3 {
-                    if grid[x2][i] != ItemType::Air {
-                        break 'scan;
-                    }
-                }
+            while x2 > x1 && (0..3).all(|i| is_empty(x2, i)) {
                 x2 -= 1;
             }
 
-            // Get the bottom right corner x
+            // Get the bottom right corner y
             let mut y2 = 2;
-            'scan: while y2 > y1 {
-                for i in 0..3 {
-                    if grid[i][y2] != ItemType::Air {
-                        break 'scan;
-                    }
-                }
+            while y2 > y1 && (0..3).all(|i| is_empty(i, y2)) {
                 y2 -= 1;
             }
 
@@ -102,62 +149,168 @@ impl Craft {
             // Check if it matches the shape
             for x in 0..3 {
                 for y in 0..3 {
+                    let slot = self.pattern[x][y];
                     if x <= width && y <= height {
                         // Check if the pattern is right
-                        if self.pattern[x][y] != grid[x1 + x][y1 + y] {
-                            return false;
-                        }
-                    } else {
-                        // Check if there is no items outside of the pattern
-                        if self.pattern[x][y] != ItemType::Air {
-                            return false;
+                        if !slot.accepts(grid[x1 + x][y1 + y]) {
+                            return None;
                         }
+                        consumption[x1 + x][y1 + y] = slot;
+                    } else if slot.ingredient != Ingredient::None {
+                        // There must be no items outside of the pattern
+                        return None;
                     }
                 }
             }
 
-            true
+            Some(consumption)
         } else {
             // The max size is 3*3
-            let mut items_craft: heapless::Vec<ItemType, 9> = heapless::Vec::new();
-            let mut items_grid: heapless::Vec<ItemType, 9> = heapless::Vec::new();
+            let mut grid_slots: heapless::Vec<(usize, usize), 9> = heapless::Vec::new();
+            let mut craft_slots: heapless::Vec<CraftSlot, 9> = heapless::Vec::new();
 
             for x in 0..3 {
                 for y in 0..3 {
-                    let item = self.pattern[x][y];
                     // 3 * 3 = 9 so if it fails, mathematics are broken
-                    items_craft.push(item).unwrap();
-                    let item = grid[x][y];
-                    items_grid.push(item).unwrap();
+                    if !is_empty(x, y) {
+                        grid_slots.push((x, y)).unwrap();
+                    }
+                    if self.pattern[x][y].ingredient != Ingredient::None {
+                        craft_slots.push(self.pattern[x][y]).unwrap();
+                    }
                 }
             }
 
-            // Check if the items are the same
-            // These loop and sub loops do 162 iterations but can be optimised. TODO
-            for item in items_grid.iter() {
-                let count_grid = items_grid.iter().filter(|v| *v == item).count();
-                let count_craft = items_craft.iter().filter(|v| *v == item).count();
+            if grid_slots.len() != craft_slots.len() {
+                return None;
+            }
 
-                if count_grid != count_craft {
-                    return false;
+            let mut used = [false; 9];
+            if Self::assign_ingredients(
+                grid,
+                &grid_slots,
+                &craft_slots,
+                &mut used,
+                &mut consumption,
+            ) {
+                Some(consumption)
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Return the grid slot of each ingredient in a square grid of the given size. Return None if the craft doesn't fit.
+    fn get_placement(&self, size: usize) -> Option<heapless::Vec<(usize, CraftSlot), 9>> {
+        let mut placement: heapless::Vec<(usize, CraftSlot), 9> = heapless::Vec::new();
+
+        // The pattern is stored column by column but the grid slots are ordered line by line
+        for y in 0..3 {
+            for x in 0..3 {
+                let slot = self.pattern[x][y];
+                if slot.ingredient == Ingredient::None {
+                    continue;
                 }
+
+                let grid_slot = if self.shaped {
+                    if x >= size || y >= size {
+                        return None;
+                    }
+                    x + y * size
+                } else {
+                    // The ingredients of a shapeless craft fill the grid in order
+                    if placement.len() >= size * size {
+                        return None;
+                    }
+                    placement.len()
+                };
+                placement.push((grid_slot, slot)).unwrap();
             }
+        }
 
-            true
+        Some(placement)
+    }
+
+    /// Choose the items of the inventory used for each ingredient placed in a grid of the given size.
+    /// Return the grid slot, the item type and the amount of each ingredient, or None if some items are missing.
+    fn find_ingredients(
+        &self,
+        inventory: &Inventory,
+        size: usize,
+    ) -> Option<heapless::Vec<(usize, ItemType, u8), 9>> {
+        let placement = self.get_placement(size)?;
+
+        // The number of items of each type not used by the previous ingredients
+        let mut available = [0u16; 256];
+        for item_stack in inventory.get_all_slots() {
+            available[item_stack.get_item_type() as usize] += item_stack.get_amount() as u16;
+        }
+
+        let mut ingredients = heapless::Vec::new();
+        for (grid_slot, slot) in placement {
+            let amount = slot.amount as u16;
+            let item_type = match slot.ingredient {
+                Ingredient::None => continue,
+                Ingredient::Item(item_type) => item_type,
+                Ingredient::Group(group) => inventory
+                    .get_all_slots()
+                    .iter()
+                    .map(|item_stack| item_stack.get_item_type())
+                    .find(|&item_type| {
+                        group.contains(item_type) && available[item_type as usize] >= amount
+                    })?,
+            };
+
+            if available[item_type as usize] < amount {
+                return None;
+            }
+            available[item_type as usize] -= amount;
+            ingredients
+                .push((grid_slot, item_type, slot.amount))
+                .unwrap();
         }
+
+        Some(ingredients)
+    }
+
+    /// Give each of the grid slots a different unused craft slot that accepts its content.
+    /// A group can accept the items of another ingredient, so we have to backtrack when an assignment fails.
+    fn assign_ingredients(
+        grid: &CraftingGrid,
+        grid_slots: &[(usize, usize)],
+        craft_slots: &[CraftSlot],
+        used: &mut [bool; 9],
+        consumption: &mut Consumption,
+    ) -> bool {
+        let Some(&(x, y)) = grid_slots.first() else {
+            return true;
+        };
+
+        for (i, slot) in craft_slots.iter().enumerate() {
+            if !used[i] && slot.accepts(grid[x][y]) {
+                used[i] = true;
+                consumption[x][y] = *slot;
+                if Self::assign_ingredients(grid, &grid_slots[1..], craft_slots, used, consumption)
+                {
+                    return true;
+                }
+                used[i] = false;
+            }
+        }
+
+        false
     }
 }
 
-const CRAFTS: [Craft; 3] = [
-    Craft::new(include_bytes!("../../target/crafts/planks.bin")),
-    Craft::new(include_bytes!("../../target/crafts/stone.bin")),
-    Craft::new(include_bytes!("../../target/crafts/grass.bin")),
-];
+// The table is generated by build.rs from the crafts directory
+include!("../../target/crafts/crafts.rs");
 
 pub struct CraftingManager {
     pub crafting_inventory_2x2: Inventory,
     pub crafting_inventory_3x3: Inventory,
-    valid_craft: bool,
+    /// What the craft shown in the result slot takes from the grid
+    consumption_2x2: Option<Consumption>,
+    consumption_3x3: Option<Consumption>,
 }
 
 impl CraftingManager {
@@ -167,52 +320,149 @@ impl CraftingManager {
         CraftingManager {
             crafting_inventory_2x2,
             crafting_inventory_3x3,
-            valid_craft: false,
+            consumption_2x2: None,
+            consumption_3x3: None,
         }
     }
 
-    pub fn update_2x2(&mut self) {
-        // Remove the recipies if the player picked up the item
-        if self.valid_craft
-            && self
-                .crafting_inventory_2x2
-                .get_item_type_at_slot_index(4)
-                .unwrap()
-                == ItemType::Air
-        {
-            for i in 0..=3 {
-                self.crafting_inventory_2x2.take_one(i);
+    pub fn get_crafts_count() -> usize {
+        CRAFTS.len()
+    }
+
+    pub fn get_craft_result(index: usize) -> ItemStack {
+        CRAFTS[index].result.clone()
+    }
+
+    /// Return the ingredients of the craft ordered like the slots of the 3x3 grid. Groups are shown with their icon.
+    pub fn get_craft_preview(index: usize) -> [ItemStack; 9] {
+        let mut preview = core::array::from_fn(|_| ItemStack::void());
+
+        for (grid_slot, slot) in CRAFTS[index].get_placement(3).unwrap() {
+            let item_type = match slot.ingredient {
+                Ingredient::None => ItemType::Air,
+                Ingredient::Item(item_type) => item_type,
+                Ingredient::Group(group) => group.get_icon(),
+            };
+            preview[grid_slot] = ItemStack::new(item_type, slot.amount, false);
+        }
+
+        preview
+    }
+
+    /// Return true if the inventory has the ingredients of the craft and if it fits in a grid of the given size
+    pub fn can_craft(index: usize, size: usize, inventory: &Inventory) -> bool {
+        CRAFTS[index].find_ingredients(inventory, size).is_some()
+    }
+
+    /// Move the ingredients of the craft from the player inventory to the empty 2x2 grid.
+    /// Return false if the player doesn't have them.
+    pub fn fill_grid_2x2(&mut self, index: usize, player_inventory: &mut Inventory) -> bool {
+        let Some(ingredients) = CRAFTS[index].find_ingredients(player_inventory, 2) else {
+            return false;
+        };
+
+        for (grid_slot, item_type, amount) in ingredients {
+            // The ingredient can be spread over several stacks
+            let mut grid_stack = ItemStack::void();
+            for slot in 0..player_inventory.get_all_slots().len() {
+                let item_stack = &player_inventory.get_all_slots()[slot];
+                if grid_stack.get_amount() == amount
+                    || item_stack.get_item_type() != item_type
+                    || (grid_stack.get_item_type() != ItemType::Air
+                        && !grid_stack.can_stack_with(item_stack))
+                {
+                    continue;
+                }
+
+                if let Some(taken) =
+                    player_inventory.take_amount(slot, amount - grid_stack.get_amount())
+                {
+                    grid_stack = taken.with_amount(grid_stack.get_amount() + taken.get_amount());
+                }
             }
+            self.crafting_inventory_2x2
+                .replace_slot_item_stack(grid_slot, grid_stack);
         }
 
-        let mut grid = [[ItemType::Air; 3]; 3];
+        true
+    }
+
+    /// The leftovers that don't fit in the grid go to the player inventory, or are dropped if it is full
+    pub fn update_2x2(&mut self, player_inventory: &mut Inventory, world: &mut World) {
+        Self::update_crafting_inventory(
+            &mut self.crafting_inventory_2x2,
+            2,
+            &mut self.consumption_2x2,
+            player_inventory,
+            world,
+        );
+    }
+
+    /// The leftovers that don't fit in the grid go to the player inventory, or are dropped if it is full
+    pub fn update_3x3(&mut self, player_inventory: &mut Inventory, world: &mut World) {
+        Self::update_crafting_inventory(
+            &mut self.crafting_inventory_3x3,
+            3,
+            &mut self.consumption_3x3,
+            player_inventory,
+            world,
+        );
+    }
+
+    /// Update a square crafting grid of the given size. The slots of the grid come first, then the result slot.
+    fn update_crafting_inventory(
+        inventory: &mut Inventory,
+        size: usize,
+        current_consumption: &mut Option<Consumption>,
+        player_inventory: &mut Inventory,
+        world: &mut World,
+    ) {
+        let result_slot = size * size;
+
+        // Remove the ingredients if the player picked up the item
+        if let Some(consumption) = current_consumption
+            && inventory.get_item_type_at_slot_index(result_slot).unwrap() == ItemType::Air
+        {
+            for slot in 0..result_slot {
+                let consumed = consumption[slot % size][slot / size];
+                inventory.take_amount(slot, consumed.amount);
 
-        // The inventory slots indexes must be from 0 to 3 included
-        for x in 0..2 {
-            for y in 0..2 {
-                grid[x][y] = self
-                    .crafting_inventory_2x2
-                    .get_item_type_at_slot_index(x + y * 2)
-                    .unwrap(); // If it fails, a cosmic particle just hit the calculators Ram! Incredible!
+                if consumed.leftover != ItemType::Air {
+                    let leftover = ItemStack::new(consumed.leftover, 1, false);
+                    if inventory.get_item_type_at_slot_index(slot).unwrap() == ItemType::Air {
+                        inventory.replace_slot_item_stack(slot, leftover);
+                    } else {
+                        let remaining = player_inventory.add_item_stack(leftover.clone());
+                        if remaining != 0 {
+                            let pos = world.get_player_entity().pos;
+                            world.spawn_item_entity(pos, leftover.with_amount(remaining));
+                        }
+                    }
+                }
             }
+            *current_consumption = None;
+        }
+
+        let mut grid = [[(ItemType::Air, 0); 3]; 3];
+
+        // The inventory slots indexes must be from 0 to size * size - 1 included
+        for slot in 0..result_slot {
+            let item_stack = inventory.get_item_stack_at_slot_index(slot).unwrap(); // If it fails, a cosmic particle just hit the calculators Ram! Incredible!
+            grid[slot % size][slot / size] = (item_stack.get_item_type(), item_stack.get_amount());
         }
 
         // Check for all crafts to match with our grid
-        if self.crafting_inventory_2x2.modified {
-            let mut found_craft = false;
-            for craft in CRAFTS {
-                if craft.matches(grid) {
-                    self.crafting_inventory_2x2
-                        .replace_slot_item_stack(4, craft.result);
-                    self.valid_craft = true;
-                    found_craft = true;
+        if inventory.modified {
+            *current_consumption = None;
+            for craft in CRAFTS.iter() {
+                if let Some(consumption) = craft.get_consumption(&grid) {
+                    inventory.replace_slot_item_stack(result_slot, craft.result.clone());
+                    *current_consumption = Some(consumption);
                     break;
                 }
             }
-            if !found_craft {
-                self.crafting_inventory_2x2
-                    .replace_slot_item_stack(4, ItemStack::void());
-                self.valid_craft = false;
+            if current_consumption.is_none() {
+                inventory.replace_slot_item_stack(result_slot, ItemStack::void());
             }
         }
     }
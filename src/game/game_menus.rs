@@ -613,6 +613,74 @@ impl Game {
             nadk::time::wait_milliseconds(50);
         }
     }
+
+    /// Drop the inventory of the player and let it respawn or leave the world
+    pub fn death_screen_loop(&mut self) -> GameState {
+        self.player.drop_inventory(&mut self.world);
+        self.hud.sync(&self.player);
+
+        let mut menu = Menu::new(Vector2::new(10, 70), 300, 2)
+            .with_element(MenuElement::Label {
+                text: "You died!".to_string(),
+                text_anchor: TextAnchor::Center,
+                allow_margin: true,
+            })
+            .with_element(MenuElement::Button {
+                text: "Respawn".to_string(),
+                is_pressed: false,
+                allow_margin: true,
+                id: 0,
+            })
+            .with_element(MenuElement::Button {
+                text: "Save and quit".to_string(),
+                is_pressed: false,
+                allow_margin: false,
+                id: 1,
+            });
+
+        // Clear the hud and blur the world behind the menu
+        self.renderer
+            .draw_game(&mut self.world, &self.player, 0, &self.hud, false);
+        self.renderer.blur_screen();
+
+        self.timing_manager.reset();
+
+        loop {
+            self.input_manager.update();
+            self.timing_manager.update();
+            self.input_manager.update_timing(&self.timing_manager);
+
+            menu.check_inputs(&self.input_manager);
+            for element in menu.get_elements() {
+                match element {
+                    MenuElement::Button {
+                        // Respawn
+                        id: 0,
+                        is_pressed: true,
+                        ..
+                    } => {
+                        self.respawn_player();
+                        return GameState::InGame;
+                    }
+                    MenuElement::Button {
+                        // Save and quit
+                        id: 1,
+                        is_pressed: true,
+                        ..
+                    } => {
+                        self.respawn_player();
+                        self.exit_world();
+                        return GameState::GoMainMenu;
+                    }
+                    _ => (),
+                }
+            }
+            menu.finish_buttons_handling();
+
+            self.renderer.draw_menu(&mut menu);
+            nadk::time::wait_milliseconds(50);
+        }
+    }
 }
 
 #[derive(Clone, Copy)]
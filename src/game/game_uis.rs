@@ -1,26 +1,61 @@
+use enum_iterator::all;
+
 use crate::{
-    constants::ItemType,
+    constants::{BlockType, CreativeCategory, ItemType},
     game::*,
     game_ui::{ContainerNeighbors, GameUIElements, NeighborDirection},
     inventory::Inventory,
+    nadk::keyboard::Key,
+    world::furnace::{FURNACE_FUEL_SLOT, FURNACE_INPUT_SLOT, FURNACE_OUTPUT_SLOT},
 };
 
 pub enum PlayerInventoryPage {
     Survival,
     Creative,
+    RecipeBook,
 }
 
+/// The recipe book shows 6 * 4 recipes per page
+const RECIPE_BOOK_PAGE_SIZE: usize = 24;
+
+/// The creative inventory shows 3 * 7 items per page
+const CREATIVE_PAGE_SIZE: usize = 21;
+
+const MAX_CREATIVE_SEARCH_LENGTH: usize = 16;
+
 impl Game {
     pub fn player_inventory_loop(&mut self, page: PlayerInventoryPage) -> GameState {
-        match page {
-            PlayerInventoryPage::Survival => self.player_inventory_survival_loop(),
-            PlayerInventoryPage::Creative => self.player_inventory_creative_loop(),
+        let mut page = Some(page);
+
+        // The pages can open each other until the inventory is closed
+        while let Some(current_page) = page {
+            page = match current_page {
+                PlayerInventoryPage::Survival => self.player_inventory_survival_loop(),
+                PlayerInventoryPage::Creative => {
+                    self.player_inventory_creative_loop();
+                    None
+                }
+                PlayerInventoryPage::RecipeBook => self.recipe_book_loop(),
+            };
         }
 
         GameState::InGame
     }
 
-    fn player_inventory_survival_loop(&mut self) {
+    /// Open the interface of the block at the given position
+    pub fn block_ui_loop(&mut self, pos: Vector3<isize>) -> GameState {
+        match self.world.chunks_manager.get_block_in_world(pos) {
+            Some(BlockType::Chest) => self.chest_loop(pos),
+            Some(BlockType::CraftingTable) => self.crafting_table_loop(),
+            Some(BlockType::Furnace) => self.furnace_loop(pos),
+            _ => {}
+        }
+
+        GameState::InGame
+    }
+
+    /// Return the page to open next, if any. [alpha] opens the recipe book.
+    fn player_inventory_survival_loop(&mut self) -> Option<PlayerInventoryPage> {
         // Clear the hud
         self.renderer
             .draw_game(&mut self.world, &self.player, 0, &self.hud, false);
@@ -69,34 +104,251 @@ impl Game {
             self.input_manager.update();
             self.timing_manager.update();
             self.input_manager.update_timing(&self.timing_manager);
-            self.crafting_manager.update_2x2();
+            self.crafting_manager
+                .update_2x2(&mut self.player.inventory, &mut self.world);
 
             let mut inventories = [
                 &mut self.player.inventory,
                 &mut self.crafting_manager.crafting_inventory_2x2,
             ];
 
+            let keep_open = ui.update(&self.input_manager, &mut inventories);
+            if !keep_open {
+                Self::give_back_crafting_items(&mut inventories, 4, &mut self.world);
+            }
+
+            self.throw_dropped_items(&mut ui);
+            if !keep_open {
+                return None;
+            }
+
+            // The items of the crafting grid stay in place while the recipe book is open
+            if self.input_manager.is_just_pressed(Key::Alpha) && ui.selected_id.is_none() {
+                return Some(PlayerInventoryPage::RecipeBook);
+            }
+
+            self.renderer.draw_game_ui(&mut ui);
+
+            nadk::display::wait_for_vblank();
+            nadk::time::wait_milliseconds(50);
+        }
+    }
+
+    /// List all the crafts. The ones that the player can do in the 2x2 grid are highlighted.
+    /// Selecting one of them fills the crafting grid and goes back to the player inventory.
+    /// [+] and [-] change the page.
+    fn recipe_book_loop(&mut self) -> Option<PlayerInventoryPage> {
+        // Clear the hud
+        self.renderer
+            .draw_game(&mut self.world, &self.player, 0, &self.hud, false);
+
+        let page_count = CraftingManager::get_crafts_count().div_ceil(RECIPE_BOOK_PAGE_SIZE);
+
+        let inventories = [&mut self.player.inventory];
+
+        let mut ui = GameUI::new(true)
+            .with_display_slot_grid(Vector2::new(10, 25), 6, 4, 0)
+            .with_display_slot_grid(Vector2::new(218, 25), 3, 3, 24)
+            .with_element(
+                GameUIElements::create_display_slot(),
+                Vector2::new(250, 137),
+                33,
+                ContainerNeighbors::default(),
+            )
+            .with_element(
+                GameUIElements::Label {
+                    text: String::new(),
+                },
+                Vector2::new(10, 161),
+                34,
+                ContainerNeighbors::default(),
+            )
+            .sync(&inventories);
+
+        ui.selected_amount = None;
+
+        let mut page = 0;
+        let mut need_page_update = true;
+        let mut previewed_craft = None;
+
+        self.timing_manager.reset();
+
+        loop {
+            self.input_manager.update();
+            self.timing_manager.update();
+            self.input_manager.update_timing(&self.timing_manager);
+
+            let mut inventories = [&mut self.player.inventory];
+
             if !ui.update(&self.input_manager, &mut inventories) {
-                // Bring the items back in the inventory
-                for slot in 0..4 {
-                    let item_stack = inventories[1].get_all_slots()[slot].clone();
-                    if item_stack.get_item_type() != ItemType::Air {
-                        let remaining = inventories[0].add_item_stack(item_stack);
-                        if remaining != 0 {
-                            // Hum... wait?!
-                            // I have no choice... Spawn the item.
-                            // I should be carreful about duplication here...
-                            let pos = self.world.get_player_entity().pos;
-                            self.world.spawn_item_entity(
-                                pos,
-                                ItemStack::new(item_stack.get_item_type(), remaining, false),
-                            );
-                        }
+                return Some(PlayerInventoryPage::Survival);
+            }
+
+            if self.input_manager.is_just_pressed(Key::Plus) && page + 1 < page_count {
+                page += 1;
+                need_page_update = true;
+            } else if self.input_manager.is_just_pressed(Key::Minus) && page > 0 {
+                page -= 1;
+                need_page_update = true;
+            }
+
+            if need_page_update {
+                for i in 0..RECIPE_BOOK_PAGE_SIZE {
+                    let index = page * RECIPE_BOOK_PAGE_SIZE + i;
+                    if index < CraftingManager::get_crafts_count() {
+                        ui.set_display_slot(
+                            i,
+                            CraftingManager::get_craft_result(index),
+                            CraftingManager::can_craft(index, 2, &self.player.inventory),
+                        );
+                    } else {
+                        ui.set_display_slot(i, ItemStack::void(), false);
                     }
                 }
+                ui.set_label_text(34, format!("Page {}/{}", page + 1, page_count).as_str());
+                need_page_update = false;
+            }
+
+            let craft_index = page * RECIPE_BOOK_PAGE_SIZE + ui.cursor_id;
+            let hovered_craft =
+                (craft_index < CraftingManager::get_crafts_count()).then_some(craft_index);
+
+            // Show the ingredients of the craft under the cursor
+            if hovered_craft != previewed_craft {
+                let preview = hovered_craft.map(CraftingManager::get_craft_preview);
+                for i in 0..9 {
+                    let item_stack = preview
+                        .as_ref()
+                        .map_or(ItemStack::void(), |preview| preview[i].clone());
+                    ui.set_display_slot(24 + i, item_stack, false);
+                }
+                let result = hovered_craft.map_or(ItemStack::void(), |index| {
+                    CraftingManager::get_craft_result(index)
+                });
+                ui.set_display_slot(33, result, false);
+                previewed_craft = hovered_craft;
+            }
+
+            if self.input_manager.is_just_pressed(Key::Ok)
+                && let Some(index) = hovered_craft
+                && CraftingManager::can_craft(index, 2, &self.player.inventory)
+            {
+                let mut inventories = [
+                    &mut self.player.inventory,
+                    &mut self.crafting_manager.crafting_inventory_2x2,
+                ];
+                Self::give_back_crafting_items(&mut inventories, 4, &mut self.world);
+
+                self.crafting_manager
+                    .fill_grid_2x2(index, &mut self.player.inventory);
+                return Some(PlayerInventoryPage::Survival);
+            }
+
+            self.renderer.draw_game_ui(&mut ui);
+
+            nadk::display::wait_for_vblank();
+            nadk::time::wait_milliseconds(50);
+        }
+    }
+
+    /// Bring the items of the crafting grid back in the player inventory, then clear the crafting grid.
+    /// inventories[0] is the player inventory and inventories[1] the crafting inventory.
+    fn give_back_crafting_items(
+        inventories: &mut [&mut Inventory; 2],
+        grid_slots: usize,
+        world: &mut World,
+    ) {
+        for slot in 0..grid_slots {
+            let item_stack = inventories[1].get_all_slots()[slot].clone();
+            if item_stack.get_item_type() != ItemType::Air {
+                let remaining = inventories[0].add_item_stack(item_stack.clone());
+                if remaining != 0 {
+                    // Hum... wait?!
+                    // I have no choice... Spawn the item.
+                    // I should be carreful about duplication here...
+                    let pos = world.get_player_entity().pos;
+                    world.spawn_item_entity(pos, item_stack.with_amount(remaining));
+                }
+            }
+        }
+
+        // The result slot is updated by the crafting manager once it sees the empty grid
+        for slot in 0..grid_slots {
+            inventories[1].replace_slot_item_stack(slot, ItemStack::void());
+        }
+    }
+
+    /// Throw in the world the items dragged out of the interface
+    fn throw_dropped_items(&mut self, ui: &mut GameUI) {
+        for item_stack in ui.take_dropped_items() {
+            Player::throw_item_stack(&mut self.world, &self.renderer.camera, item_stack);
+        }
+    }
+
+    fn crafting_table_loop(&mut self) {
+        // Clear the hud
+        self.renderer
+            .draw_game(&mut self.world, &self.player, 0, &self.hud, false);
+
+        let inventories = [
+            &mut self.player.inventory,
+            &mut self.crafting_manager.crafting_inventory_3x3,
+        ];
+
+        let mut ui = GameUI::new(true)
+            .with_slot_grid(Vector2::new(65, 110), 6, 3, 0, 0, 6)
+            .with_slot_grid(Vector2::new(65, 208), 6, 1, 0, 18, 0)
+            .with_slot_grid(Vector2::new(81, 8), 3, 3, 1, 24, 0)
+            .with_element(
+                GameUIElements::create_one_way_slot_slot(1, 9),
+                Vector2::new(209, 40),
+                33,
+                ContainerNeighbors::default(),
+            )
+            .with_element(
+                GameUIElements::Arrow { filling: 0. },
+                Vector2::new(177, 40),
+                34,
+                ContainerNeighbors::default(),
+            )
+            .with_links(&[
+                (12, 18, NeighborDirection::Bottom),
+                (13, 19, NeighborDirection::Bottom),
+                (14, 20, NeighborDirection::Bottom),
+                (15, 21, NeighborDirection::Bottom),
+                (16, 22, NeighborDirection::Bottom),
+                (17, 23, NeighborDirection::Bottom),
+                (30, 1, NeighborDirection::Bottom),
+                (31, 2, NeighborDirection::Bottom),
+                (32, 3, NeighborDirection::Bottom),
+                (33, 5, NeighborDirection::Bottom),
+                (29, 33, NeighborDirection::Right),
+            ])
+            .sync(&inventories);
+
+        ui.selected_amount = None;
+
+        self.timing_manager.reset();
+
+        loop {
+            self.input_manager.update();
+            self.timing_manager.update();
+            self.input_manager.update_timing(&self.timing_manager);
+            self.crafting_manager
+                .update_3x3(&mut self.player.inventory, &mut self.world);
+
+            let mut inventories = [
+                &mut self.player.inventory,
+                &mut self.crafting_manager.crafting_inventory_3x3,
+            ];
+
+            let keep_open = ui.update(&self.input_manager, &mut inventories);
+            if !keep_open {
+                Self::give_back_crafting_items(&mut inventories, 9, &mut self.world);
+            }
 
-                // Then clear the crafting inventory.
-                inventories[1].fill(ItemStack::void());
+            self.throw_dropped_items(&mut ui);
+            if !keep_open {
                 break;
             }
 
@@ -107,58 +359,52 @@ impl Game {
         }
     }
 
+    /// Return the items shown in the creative inventory. The search looks in all the tabs.
+    fn get_creative_items(category: CreativeCategory, search: &str) -> Vec<ItemType> {
+        (1..=u8::MAX)
+            .filter_map(ItemType::get_from_id)
+            .filter(|item_type| {
+                if search.is_empty() {
+                    item_type.get_creative_category() == Some(category)
+                } else {
+                    item_type.get_creative_category().is_some()
+                        && item_type.get_name().to_lowercase().contains(search)
+                }
+            })
+            .collect()
+    }
+
+    /// [xnt] changes the tab, [(] and [)] change the page and [backspace] clears the player inventory.
+    /// [alpha] starts typing a search with the alpha keys, [ok] or [alpha] ends it.
     fn player_inventory_creative_loop(&mut self) {
         // Clear the hud
         self.renderer
             .draw_game(&mut self.world, &self.player, 0, &self.hud, false);
 
-        let mut creative_inventory = Inventory::new(21);
-
-        creative_inventory.fill(ItemStack::new(crate::constants::ItemType::Air, 0, true));
-
-        creative_inventory.replace_slot_item_stack(
-            0,
-            ItemStack::new(crate::constants::ItemType::StoneBlock, 1, true),
-        );
-        creative_inventory.replace_slot_item_stack(
-            1,
-            ItemStack::new(crate::constants::ItemType::DirtBlock, 1, true),
-        );
-        creative_inventory.replace_slot_item_stack(
-            2,
-            ItemStack::new(crate::constants::ItemType::GrassBlock, 1, true),
-        );
-        creative_inventory.replace_slot_item_stack(
-            3,
-            ItemStack::new(crate::constants::ItemType::SandBlock, 1, true),
-        );
-        creative_inventory.replace_slot_item_stack(
-            4,
-            ItemStack::new(crate::constants::ItemType::CobblestoneBlock, 1, true),
-        );
-        creative_inventory.replace_slot_item_stack(
-            5,
-            ItemStack::new(crate::constants::ItemType::BorderBlock, 1, true),
-        );
-        creative_inventory.replace_slot_item_stack(
-            6,
-            ItemStack::new(crate::constants::ItemType::LogBlock, 1, true),
-        );
-        creative_inventory.replace_slot_item_stack(
-            7,
-            ItemStack::new(crate::constants::ItemType::LeavesBlock, 1, true),
-        );
-        creative_inventory.replace_slot_item_stack(
-            8,
-            ItemStack::new(crate::constants::ItemType::PlanksBlock, 1, true),
-        );
-
-        let mut inventories = [&mut self.player.inventory, &mut creative_inventory];
+        let mut creative_inventory = Inventory::new(CREATIVE_PAGE_SIZE);
+
+        let inventories = [&mut self.player.inventory, &mut creative_inventory];
 
         let mut ui = GameUI::new(true)
             .with_slot_grid(Vector2::new(10, 41), 6, 3, 0, 0, 6)
             .with_slot_grid(Vector2::new(10, 139), 6, 1, 0, 18, 0)
             .with_slot_grid(Vector2::new(218, 9), 3, 7, 1, 24, 0)
+            .with_element(
+                GameUIElements::Label {
+                    text: String::new(),
+                },
+                Vector2::new(10, 9),
+                45,
+                ContainerNeighbors::default(),
+            )
+            .with_element(
+                GameUIElements::Label {
+                    text: String::new(),
+                },
+                Vector2::new(10, 185),
+                46,
+                ContainerNeighbors::default(),
+            )
             .with_links(&[
                 (12, 18, NeighborDirection::Bottom),
                 (13, 19, NeighborDirection::Bottom),
@@ -175,6 +421,12 @@ impl Game {
 
         ui.selected_amount = None;
 
+        let mut category = CreativeCategory::Building;
+        let mut search = String::new();
+        let mut is_typing = false;
+        let mut page = 0;
+        let mut need_items_update = true;
+
         self.timing_manager.reset();
 
         loop {
@@ -182,7 +434,245 @@ impl Game {
             self.timing_manager.update();
             self.input_manager.update_timing(&self.timing_manager);
 
-            if !ui.update(&self.input_manager, &mut inventories) {
+            if is_typing {
+                // The typed keys must not move the cursor or the items
+                if self.input_manager.is_just_pressed(Key::Alpha)
+                    || self.input_manager.is_just_pressed(Key::Ok)
+                {
+                    is_typing = false;
+                    need_items_update = true;
+                } else if self.input_manager.is_impulsed_key(Key::Backspace) {
+                    search.pop();
+                    page = 0;
+                    need_items_update = true;
+                } else {
+                    for key in all::<Key>() {
+                        if self.input_manager.is_impulsed_key(key)
+                            && let Some(letter) = key.get_matching_char(false, true)
+                            && search.len() < MAX_CREATIVE_SEARCH_LENGTH
+                        {
+                            search.push(letter);
+                            page = 0;
+                            need_items_update = true;
+                        }
+                    }
+                }
+            } else {
+                let mut inventories = [&mut self.player.inventory, &mut creative_inventory];
+
+                let keep_open = ui.update(&self.input_manager, &mut inventories);
+                self.throw_dropped_items(&mut ui);
+                if !keep_open {
+                    break;
+                }
+
+                if self.input_manager.is_just_pressed(Key::Alpha) && ui.selected_id.is_none() {
+                    is_typing = true;
+                    need_items_update = true;
+                } else if self.input_manager.is_just_pressed(Key::Xnt) {
+                    category = category.next();
+                    search.clear();
+                    page = 0;
+                    need_items_update = true;
+                } else if self.input_manager.is_just_pressed(Key::RightParenthesis) {
+                    page += 1;
+                    need_items_update = true;
+                } else if self.input_manager.is_just_pressed(Key::LeftParenthesis) && page > 0 {
+                    page -= 1;
+                    need_items_update = true;
+                } else if self.input_manager.is_just_pressed(Key::Backspace) {
+                    ui.clear_selection();
+                    self.player.inventory.fill(ItemStack::void());
+                }
+            }
+
+            if need_items_update {
+                let items = Self::get_creative_items(category, &search);
+                let page_count = items.len().div_ceil(CREATIVE_PAGE_SIZE).max(1);
+                page = page.min(page_count - 1);
+
+                for slot in 0..CREATIVE_PAGE_SIZE {
+                    let item_type = items
+                        .get(page * CREATIVE_PAGE_SIZE + slot)
+                        .copied()
+                        .unwrap_or(ItemType::Air);
+                    creative_inventory
+                        .replace_slot_item_stack(slot, ItemStack::new(item_type, 1, true));
+                }
+
+                let title = if search.is_empty() {
+                    category.get_name()
+                } else {
+                    "Search"
+                };
+                // The labels are padded to erase the previous text
+                ui.set_label_text(
+                    45,
+                    format!("{:<22}", format!("{title} {}/{page_count}", page + 1)).as_str(),
+                );
+                let cursor = if is_typing { "_" } else { "" };
+                ui.set_label_text(
+                    46,
+                    format!("{:<22}", format!("> {search}{cursor}")).as_str(),
+                );
+
+                // Show the new items
+                let inventories = [&mut self.player.inventory, &mut creative_inventory];
+                ui.update_slots(&inventories);
+                need_items_update = false;
+            }
+
+            self.renderer.draw_game_ui(&mut ui);
+
+            nadk::display::wait_for_vblank();
+            nadk::time::wait_milliseconds(50);
+        }
+    }
+
+    fn chest_loop(&mut self, pos: Vector3<isize>) {
+        // Clear the hud
+        self.renderer
+            .draw_game(&mut self.world, &self.player, 0, &self.hud, false);
+
+        let Some(chest_inventory) = self.world.get_block_inventory_mut(pos) else {
+            return;
+        };
+
+        let inventories = [&mut self.player.inventory, chest_inventory];
+
+        let mut ui = GameUI::new(true)
+            .with_slot_grid(Vector2::new(65, 110), 6, 3, 0, 0, 6)
+            .with_slot_grid(Vector2::new(65, 208), 6, 1, 0, 18, 0)
+            .with_slot_grid(Vector2::new(65, 8), 6, 3, 1, 24, 0)
+            .with_links(&[
+                (12, 18, NeighborDirection::Bottom),
+                (13, 19, NeighborDirection::Bottom),
+                (14, 20, NeighborDirection::Bottom),
+                (15, 21, NeighborDirection::Bottom),
+                (16, 22, NeighborDirection::Bottom),
+                (17, 23, NeighborDirection::Bottom),
+                (36, 0, NeighborDirection::Bottom),
+                (37, 1, NeighborDirection::Bottom),
+                (38, 2, NeighborDirection::Bottom),
+                (39, 3, NeighborDirection::Bottom),
+                (40, 4, NeighborDirection::Bottom),
+                (41, 5, NeighborDirection::Bottom),
+            ])
+            .with_storage_inventory(1)
+            .sync(&inventories);
+
+        ui.selected_amount = None;
+
+        self.timing_manager.reset();
+
+        loop {
+            self.input_manager.update();
+            self.timing_manager.update();
+            self.input_manager.update_timing(&self.timing_manager);
+
+            let Some(chest_inventory) = self.world.get_block_inventory_mut(pos) else {
+                break;
+            };
+            let mut inventories = [&mut self.player.inventory, chest_inventory];
+
+            // The items are moved between the chest and the player with move_item_in_other_inventory
+            let keep_open = ui.update(&self.input_manager, &mut inventories);
+            self.throw_dropped_items(&mut ui);
+            if !keep_open {
+                break;
+            }
+
+            self.renderer.draw_game_ui(&mut ui);
+
+            nadk::display::wait_for_vblank();
+            nadk::time::wait_milliseconds(50);
+        }
+    }
+
+    fn furnace_loop(&mut self, pos: Vector3<isize>) {
+        // Clear the hud
+        self.renderer
+            .draw_game(&mut self.world, &self.player, 0, &self.hud, false);
+
+        let Some(furnace_inventory) = self.world.get_block_inventory_mut(pos) else {
+            return;
+        };
+
+        let inventories = [&mut self.player.inventory, furnace_inventory];
+
+        let mut ui = GameUI::new(true)
+            .with_slot_grid(Vector2::new(65, 110), 6, 3, 0, 0, 6)
+            .with_slot_grid(Vector2::new(65, 208), 6, 1, 0, 18, 0)
+            .with_element(
+                GameUIElements::create_slot(1, FURNACE_INPUT_SLOT),
+                Vector2::new(113, 8),
+                24,
+                ContainerNeighbors::default(),
+            )
+            .with_element(
+                GameUIElements::create_slot(1, FURNACE_FUEL_SLOT),
+                Vector2::new(113, 72),
+                25,
+                ContainerNeighbors::default(),
+            )
+            .with_element(
+                GameUIElements::create_one_way_slot_slot(1, FURNACE_OUTPUT_SLOT),
+                Vector2::new(209, 40),
+                26,
+                ContainerNeighbors::default(),
+            )
+            .with_element(
+                GameUIElements::Arrow { filling: 0. },
+                Vector2::new(161, 40),
+                27,
+                ContainerNeighbors::default(),
+            )
+            .with_element(
+                GameUIElements::Flame { filling: 0. },
+                Vector2::new(113, 40),
+                28,
+                ContainerNeighbors::default(),
+            )
+            .with_links(&[
+                (12, 18, NeighborDirection::Bottom),
+                (13, 19, NeighborDirection::Bottom),
+                (14, 20, NeighborDirection::Bottom),
+                (15, 21, NeighborDirection::Bottom),
+                (16, 22, NeighborDirection::Bottom),
+                (17, 23, NeighborDirection::Bottom),
+                (24, 25, NeighborDirection::Bottom),
+                (25, 2, NeighborDirection::Bottom),
+                (26, 4, NeighborDirection::Bottom),
+                (25, 26, NeighborDirection::Right),
+                (24, 26, NeighborDirection::Right),
+            ])
+            .sync(&inventories);
+
+        ui.selected_amount = None;
+
+        self.timing_manager.reset();
+
+        loop {
+            self.input_manager.update();
+            self.timing_manager.update();
+            self.input_manager.update_timing(&self.timing_manager);
+
+            // The furnace keeps smelting while its interface is open
+            self.world
+                .update_furnaces(self.timing_manager.get_delta_time());
+            if let Some(furnace) = self.world.get_furnace_state(pos) {
+                ui.set_filling(27, furnace.get_cook_progress());
+                ui.set_filling(28, furnace.get_burn_progress());
+            }
+
+            let Some(furnace_inventory) = self.world.get_block_inventory_mut(pos) else {
+                break;
+            };
+            let mut inventories = [&mut self.player.inventory, furnace_inventory];
+
+            let keep_open = ui.update(&self.input_manager, &mut inventories);
+            self.throw_dropped_items(&mut ui);
+            if !keep_open {
                 break;
             }
 
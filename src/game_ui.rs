@@ -1,7 +1,9 @@
+use core::{mem, ops::Range};
+
 use nalgebra::Vector2;
 
 use crate::{
-    constants::ItemType,
+    constants::{ItemType, player::HOTBAR_SIZE},
     nadk::keyboard::Key,
     input_manager::InputManager,
     inventory::{Inventory, ItemStack},
@@ -28,11 +30,20 @@ pub enum GameUIElements {
     Arrow {
         filling: f32,
     },
+    /// The remaining fuel of a furnace
+    Flame {
+        filling: f32,
+    },
     OneWayItemSlot {
         item_stack: ItemStack,
         inventory_id: usize,
         inventory_slot_index: usize,
     },
+    /// A slot showing an item that is not in an inventory, like the recipes of the recipe book
+    DisplaySlot {
+        item_stack: ItemStack,
+        highlighted: bool,
+    },
 }
 
 impl GameUIElements {
@@ -44,6 +55,13 @@ impl GameUIElements {
         }
     }
 
+    pub fn create_display_slot() -> Self {
+        Self::DisplaySlot {
+            item_stack: ItemStack::void(),
+            highlighted: false,
+        }
+    }
+
     pub fn create_one_way_slot_slot(inventory_id: usize, inventory_slot_index: usize) -> Self {
         Self::OneWayItemSlot {
             item_stack: ItemStack::void(),
@@ -98,6 +116,12 @@ pub struct GameUI {
     pub blur_background: bool,
 
     pub is_selecting_amount: bool,
+    /// Keep the slot selected after a move and only move one item at a time
+    place_one_by_one: bool,
+    /// An inventory used to store items, like a chest. It receives the quick-moved items and can be sorted.
+    storage_inventory_id: Option<usize>,
+    /// The items dragged out of the window. They must be thrown in the world by the caller.
+    dropped_items: Vec<ItemStack>,
 }
 
 impl GameUI {
@@ -111,10 +135,18 @@ impl GameUI {
             need_redraw: true,
             blur_background,
             is_selecting_amount: false,
+            place_one_by_one: false,
+            storage_inventory_id: None,
+            dropped_items: Vec::new(),
         };
         ui
     }
 
+    pub fn with_storage_inventory(mut self, inventory_id: usize) -> Self {
+        self.storage_inventory_id = Some(inventory_id);
+        self
+    }
+
     /// Sync the the slots with the inventories at the end of the game ui creation pipeline
     pub fn sync(mut self, inventories: &[&mut Inventory]) -> Self {
         self.update_slots(inventories);
@@ -192,9 +224,11 @@ impl GameUI {
         self.elements.iter_mut().find(|elem| elem.id == id)
     }
 
-    fn move_cursor_if_possible(&mut self, input_manager: &InputManager, key: Key) {
+    /// Move the cursor to the neighbor in the direction of the key.
+    /// Return true if the key is pressed but there is no neighbor, i.e. the cursor goes out of the window.
+    fn move_cursor_if_possible(&mut self, input_manager: &InputManager, key: Key) -> bool {
         if !input_manager.is_impulsed_key(key) {
-            return;
+            return false;
         }
 
         let elem_or_none = self.get_element_with_id(self.cursor_id);
@@ -210,14 +244,58 @@ impl GameUI {
             if let Some(neighbor_id) = neighbor {
                 self.cursor_id = neighbor_id;
                 self.ask_redraw();
+            } else {
+                return true;
             }
         }
+        false
     }
 
     pub fn ask_redraw(&mut self) {
         self.need_redraw = true;
     }
 
+    /// Set the filling of an Arrow or a Flame element. The filling is between 0 and 1.
+    pub fn set_filling(&mut self, id: usize, new_filling: f32) {
+        if let Some(element) = self.get_element_with_id_mut(id)
+            && let GameUIElements::Arrow { filling } | GameUIElements::Flame { filling } =
+                &mut element.element
+            && *filling != new_filling
+        {
+            *filling = new_filling;
+            self.ask_redraw();
+        }
+    }
+
+    pub fn set_display_slot(
+        &mut self,
+        id: usize,
+        new_item_stack: ItemStack,
+        new_highlighted: bool,
+    ) {
+        if let Some(element) = self.get_element_with_id_mut(id)
+            && let GameUIElements::DisplaySlot {
+                item_stack,
+                highlighted,
+            } = &mut element.element
+            && (*item_stack != new_item_stack || *highlighted != new_highlighted)
+        {
+            *item_stack = new_item_stack;
+            *highlighted = new_highlighted;
+            self.ask_redraw();
+        }
+    }
+
+    pub fn set_label_text(&mut self, id: usize, new_text: &str) {
+        if let Some(element) = self.get_element_with_id_mut(id)
+            && let GameUIElements::Label { text } = &mut element.element
+            && text != new_text
+        {
+            *text = String::from(new_text);
+            self.ask_redraw();
+        }
+    }
+
     pub fn update(
         &mut self,
         input_manager: &InputManager,
@@ -244,10 +322,15 @@ impl GameUI {
                 self.ask_redraw();
             }
         } else {
-            self.move_cursor_if_possible(input_manager, Key::Right);
-            self.move_cursor_if_possible(input_manager, Key::Left);
-            self.move_cursor_if_possible(input_manager, Key::Up);
-            self.move_cursor_if_possible(input_manager, Key::Down);
+            let mut out_of_window = false;
+            for key in [Key::Right, Key::Left, Key::Up, Key::Down] {
+                out_of_window |= self.move_cursor_if_possible(input_manager, key);
+            }
+
+            // Dragging the selected items out of the window drops them
+            if out_of_window && self.selected_id.is_some() {
+                self.drop_selected_items(inventories);
+            }
         }
 
         // Check for stack overflow
@@ -267,6 +350,10 @@ impl GameUI {
             }
         }
 
+        if !self.is_selecting_amount {
+            self.check_quick_actions(input_manager, inventories);
+        }
+
         // Mainly for crafting
         for inventory in inventories.iter() {
             if inventory.modified {
@@ -291,6 +378,7 @@ impl GameUI {
                 {
                     self.selected_amount = Some(item_stack.get_amount() as usize / 2);
                     self.is_selecting_amount = true;
+                    self.place_one_by_one = false;
                     self.ask_redraw();
                 }
             } else {
@@ -315,6 +403,9 @@ impl GameUI {
                         ..
                     } = end_elem.element
                 {
+                    let start_item_type = inventories[start_inventory_id]
+                        .get_item_type_at_slot_index(start_inventory_slot_index);
+
                     // If the 2 item slots are in the same inventories
                     if start_inventory_id == end_inventory_id {
                         inventories[start_inventory_id].move_item(
@@ -324,14 +415,11 @@ impl GameUI {
                         );
                         self.ask_redraw();
                     } else {
-                        // Trick to have 2 mutable references to elements in the slice
-                        let (first, second) = if start_inventory_id < end_inventory_id {
-                            let (first, second) = inventories.split_at_mut(end_inventory_id);
-                            (&mut first[start_inventory_id], &mut second[0])
-                        } else {
-                            let (first, second) = inventories.split_at_mut(start_inventory_id);
-                            (&mut second[0], &mut first[end_inventory_id])
-                        };
+                        let (first, second) = Self::get_two_inventories(
+                            inventories,
+                            start_inventory_id,
+                            end_inventory_id,
+                        );
 
                         first.move_item_in_other_inventory(
                             second,
@@ -342,9 +430,17 @@ impl GameUI {
                         self.ask_redraw();
                     }
 
-                    self.selected_id = None;
-                    self.selected_amount = None;
-                    self.is_selecting_amount = false;
+                    // In one by one mode, the slot stays selected while it has items left
+                    let has_items_left = inventories[start_inventory_id]
+                        .get_item_type_at_slot_index(start_inventory_slot_index)
+                        .is_some_and(|item_type| {
+                            item_type != ItemType::Air && Some(item_type) == start_item_type
+                        });
+                    if self.place_one_by_one && has_items_left {
+                        self.selected_amount = Some(1);
+                    } else {
+                        self.clear_selection();
+                    }
 
                     self.update_slots(inventories);
                 } else if let Some(element) = self.get_element_with_id(self.cursor_id)
@@ -364,18 +460,20 @@ impl GameUI {
                 {
                     // Here, I concider the inventory at index 0 as the player's inventory or the main inventory.
                     if *inventory_id == 1 {
-                        inventories[0].add_item_stack(item_stack.clone());
-                        inventories[1]
-                            .replace_slot_item_stack(*inventory_slot_index, ItemStack::void());
+                        // The result is only taken if it fits entirely, so that the craft is never consumed for a part of it
+                        let mut inventory = inventories[0].clone();
+                        if inventory.add_item_stack(item_stack.clone()) == 0 {
+                            *inventories[0] = inventory;
+                            inventories[1]
+                                .replace_slot_item_stack(*inventory_slot_index, ItemStack::void());
+                        }
                     }
                 }
             }
         }
         if input_manager.is_just_pressed(Key::Back) {
             if self.selected_id.is_some() {
-                self.selected_id = None;
-                self.selected_amount = None;
-                self.is_selecting_amount = false;
+                self.clear_selection();
             } else {
                 return false;
             }
@@ -388,8 +486,219 @@ impl GameUI {
         true
     }
 
+    /// Return the items dragged out of the window since the last call
+    pub fn take_dropped_items(&mut self) -> Vec<ItemStack> {
+        mem::take(&mut self.dropped_items)
+    }
+
+    /// Remove the selected items from their slot and add them to the dropped items
+    fn drop_selected_items(&mut self, inventories: &mut [&mut Inventory]) {
+        let Some(element) = self.selected_id.and_then(|id| self.get_element_with_id(id)) else {
+            return;
+        };
+        let GameUIElements::ItemSlot {
+            inventory_id,
+            inventory_slot_index,
+            ..
+        } = element.element
+        else {
+            return;
+        };
+
+        let amount = self
+            .selected_amount
+            .map_or(u8::MAX, |amount| amount.min(u8::MAX as usize) as u8);
+        let inventory = &mut inventories[inventory_id];
+        if let Some(item_stack) = inventory.take_amount(inventory_slot_index, amount) {
+            self.dropped_items.push(item_stack);
+        }
+
+        // In one by one mode, the slot stays selected while it has items left
+        let has_items_left = inventory
+            .get_item_type_at_slot_index(inventory_slot_index)
+            .is_some_and(|item_type| item_type != ItemType::Air);
+        if self.place_one_by_one && has_items_left {
+            self.selected_amount = Some(1);
+        } else {
+            self.clear_selection();
+        }
+
+        self.update_slots(inventories);
+        self.ask_redraw();
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.selected_id = None;
+        self.selected_amount = None;
+        self.is_selecting_amount = false;
+        self.place_one_by_one = false;
+    }
+
+    /// Trick to have 2 mutable references to elements in the slice
+    fn get_two_inventories<'a>(
+        inventories: &'a mut [&mut Inventory],
+        first_id: usize,
+        second_id: usize,
+    ) -> (&'a mut Inventory, &'a mut Inventory) {
+        if first_id < second_id {
+            let (first, second) = inventories.split_at_mut(second_id);
+            (&mut first[first_id], &mut second[0])
+        } else {
+            let (first, second) = inventories.split_at_mut(first_id);
+            (&mut second[0], &mut first[second_id])
+        }
+    }
+
+    /// Handle the shortcuts on the slot under the cursor:
+    /// [÷] selects half of the stack, [+] moves the items one by one,
+    /// [shift] quick-moves the stack and [toolbox] sorts the inventory
+    fn check_quick_actions(
+        &mut self,
+        input_manager: &InputManager,
+        inventories: &mut [&mut Inventory],
+    ) {
+        let Some(element) = self.get_element_with_id(self.cursor_id) else {
+            return;
+        };
+        let GameUIElements::ItemSlot {
+            item_stack,
+            inventory_id,
+            inventory_slot_index,
+        } = &element.element
+        else {
+            return;
+        };
+        let (inventory_id, inventory_slot_index) = (*inventory_id, *inventory_slot_index);
+        let amount = item_stack.get_amount() as usize;
+        let has_items = item_stack.get_item_type() != ItemType::Air;
+
+        if input_manager.is_just_pressed(Key::Division) && has_items {
+            // Split the stack in half, the bigger half is moved
+            self.selected_id = Some(self.cursor_id);
+            self.selected_amount = Some(amount.div_ceil(2));
+            self.place_one_by_one = false;
+            self.ask_redraw();
+        } else if input_manager.is_just_pressed(Key::Plus) && has_items {
+            self.selected_id = Some(self.cursor_id);
+            self.selected_amount = Some(1);
+            self.place_one_by_one = true;
+            self.ask_redraw();
+        } else if input_manager.is_just_pressed(Key::Shift) && has_items {
+            self.clear_selection();
+            self.quick_move(inventories, inventory_id, inventory_slot_index);
+        } else if input_manager.is_just_pressed(Key::Toolbox) {
+            self.clear_selection();
+            if inventory_id == 0 {
+                // The hotbar is left as it is
+                let size = inventories[0].get_all_slots().len();
+                inventories[0].sort(HOTBAR_SIZE..size);
+            } else if self.storage_inventory_id == Some(inventory_id) {
+                let size = inventories[inventory_id].get_all_slots().len();
+                inventories[inventory_id].sort(0..size);
+            }
+        }
+    }
+
+    /// Send the stack at the given slot to the other side of the interface.
+    /// The items go from the player inventory to the storage inventory and back,
+    /// or between the hotbar and the main grid when there is no storage inventory.
+    fn quick_move(
+        &self,
+        inventories: &mut [&mut Inventory],
+        inventory_id: usize,
+        slot_index: usize,
+    ) {
+        let Some(item_stack) = inventories[inventory_id]
+            .get_ref_to_slot(slot_index)
+            .cloned()
+        else {
+            return;
+        };
+
+        // Infinite stacks are given to the player
+        if item_stack.creative_slot {
+            let max_stack = item_stack.get_item_type().get_max_stack_amount();
+            inventories[0].add_item_stack(item_stack.with_amount(max_stack));
+            return;
+        }
+
+        let (target_inventory_id, target_range): (usize, Range<usize>) = if inventory_id != 0 {
+            (0, 0..usize::MAX)
+        } else if let Some(storage_id) = self.storage_inventory_id {
+            (storage_id, 0..usize::MAX)
+        } else if slot_index < HOTBAR_SIZE {
+            (0, HOTBAR_SIZE..usize::MAX)
+        } else {
+            (0, 0..HOTBAR_SIZE)
+        };
+
+        // Only the slots shown in the interface can receive the items
+        let target_slots: Vec<usize> = self
+            .elements
+            .iter()
+            .filter_map(|container| match container.element {
+                GameUIElements::ItemSlot {
+                    inventory_id,
+                    inventory_slot_index,
+                    ..
+                } if inventory_id == target_inventory_id
+                    && target_range.contains(&inventory_slot_index) =>
+                {
+                    Some(inventory_slot_index)
+                }
+                _ => None,
+            })
+            .collect();
+
+        // Complete the partial stacks first, then fill the empty slots
+        for fill_empty_slots in [false, true] {
+            for &target_slot in target_slots.iter() {
+                let Some(start) = inventories[inventory_id].get_ref_to_slot(slot_index) else {
+                    return;
+                };
+                if start.get_item_type() == ItemType::Air {
+                    return;
+                }
+                let Some(end) = inventories[target_inventory_id].get_ref_to_slot(target_slot)
+                else {
+                    continue;
+                };
+
+                let max_stack = start.get_item_type().get_max_stack_amount();
+                let amount = if fill_empty_slots {
+                    if end.get_item_type() != ItemType::Air {
+                        continue;
+                    }
+                    start.get_amount()
+                } else {
+                    if !end.can_stack_with(start) || end.get_amount() >= max_stack {
+                        continue;
+                    }
+                    start.get_amount().min(max_stack - end.get_amount())
+                };
+
+                if target_inventory_id == inventory_id {
+                    inventories[inventory_id].move_item(
+                        slot_index,
+                        target_slot,
+                        Some(amount as usize),
+                    );
+                } else {
+                    let (start_inventory, end_inventory) =
+                        Self::get_two_inventories(inventories, inventory_id, target_inventory_id);
+                    start_inventory.move_item_in_other_inventory(
+                        end_inventory,
+                        slot_index,
+                        target_slot,
+                        Some(amount as usize),
+                    );
+                }
+            }
+        }
+    }
+
     /// Sync the GameUI slots elements with the matching inventories slots
-    fn update_slots(&mut self, inventories: &[&mut Inventory]) {
+    pub fn update_slots(&mut self, inventories: &[&mut Inventory]) {
         for element in &mut self.elements {
             if let GameUIElements::ItemSlot {
                 item_stack,
@@ -415,7 +724,7 @@ impl GameUI {
     }
 
     pub fn with_slot_grid(
-        mut self,
+        self,
         pos: Vector2<u16>,
         width: u16,
         height: u16,
@@ -423,12 +732,39 @@ impl GameUI {
         start_id: usize,
         start_inventory_index: usize,
     ) -> Self {
-        let mut last_inventory_index = start_inventory_index;
+        self.with_grid(pos, width, height, start_id, |index| {
+            GameUIElements::create_slot(inventory_id, start_inventory_index + index)
+        })
+    }
+
+    /// Add a grid of display slots, empty until they are set with set_display_slot
+    pub fn with_display_slot_grid(
+        self,
+        pos: Vector2<u16>,
+        width: u16,
+        height: u16,
+        start_id: usize,
+    ) -> Self {
+        self.with_grid(pos, width, height, start_id, |_| {
+            GameUIElements::create_display_slot()
+        })
+    }
+
+    /// Add a grid of linked elements. The function creates the element from its index in the grid.
+    fn with_grid(
+        mut self,
+        pos: Vector2<u16>,
+        width: u16,
+        height: u16,
+        start_id: usize,
+        create_element: impl Fn(usize) -> GameUIElements,
+    ) -> Self {
+        let mut index = 0;
         let mut last_element_id = start_id;
 
         for y in 0..height {
             for x in 0..width {
-                let slot = GameUIElements::create_slot(inventory_id, last_inventory_index);
+                let element = create_element(index);
                 let neighbors = ContainerNeighbors {
                     up_id: if y != 0 {
                         Some(last_element_id - width as usize)
@@ -452,10 +788,10 @@ impl GameUI {
                     },
                 };
 
-                let slot_pos = Vector2::new(32 * x, 32 * y) + pos;
-                self.add_element(slot, slot_pos, last_element_id, neighbors);
+                let element_pos = Vector2::new(32 * x, 32 * y) + pos;
+                self.add_element(element, element_pos, last_element_id, neighbors);
 
-                last_inventory_index += 1;
+                index += 1;
                 last_element_id += 1;
             }
         }
@@ -1,10 +1,14 @@
-use crate::{input_manager::InputManager, inventory::ItemStack, player::Player};
+use crate::{input_manager::InputManager, inventory::ItemStack, player::Player, world::World};
 
 pub struct Hud {
     slots: [ItemStack; 6],
     pub selected_slot: usize,
     pub breaking_progress: Option<f32>,
     pub show_debug: bool,
+    /// Health of the player in half hearts. None hides the hearts.
+    pub health: Option<u8>,
+    /// Hunger of the player in half food icons. None hides the food icons.
+    pub hunger: Option<u8>,
 }
 
 impl Hud {
@@ -14,9 +18,11 @@ impl Hud {
             selected_slot: 0,
             breaking_progress: None,
             show_debug: false,
+            health: None,
+            hunger: None,
         }
     }
-    pub fn update(&mut self, input_manager: &InputManager, player: &Player) {
+    pub fn update(&mut self, input_manager: &InputManager, player: &Player, world: &World) {
         if input_manager.is_just_pressed(crate::nadk::keyboard::Key::LeftParenthesis) {
             if self.selected_slot == 0 {
                 self.selected_slot = 5;
@@ -36,13 +42,19 @@ impl Hud {
             self.show_debug = !self.show_debug;
         }
 
-        self.breaking_progress = player.get_block_breaking_progress();
+        // The same bar shows the progress of the food being eaten
+        self.breaking_progress = player
+            .get_block_breaking_progress()
+            .or(player.get_eating_progress());
+        self.health = world.get_player_entity().health;
+        // The player only gets hungry when it can take damage
+        self.hunger = self.health.map(|_| player.hunger);
     }
 
     pub fn sync(&mut self, player: &Player) {
         let inventory_slots = player.inventory.get_all_slots();
         for i in 0..6 {
-            self.slots[i] = inventory_slots[0 + i];
+            self.slots[i] = inventory_slots[0 + i].clone();
         }
     }
 
@@ -1,15 +1,23 @@
-use core::{mem, usize};
+use core::{mem, ops::Range, usize};
 
 calc_use!(alloc::vec::Vec);
 use serde::{Deserialize, Serialize};
 
 use crate::constants::ItemType;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+/// Extra data carried by an item stack. Stacks with different metadata never merge.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemMetadata {
+    /// Number of blocks mined with the tool
+    pub damage: u16,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ItemStack {
     item_type: ItemType,
     amount: u8,
     pub(crate) creative_slot: bool,
+    metadata: Option<ItemMetadata>,
 }
 
 impl ItemStack {
@@ -18,6 +26,7 @@ impl ItemStack {
             item_type: ItemType::Air,
             amount: 0,
             creative_slot: false,
+            metadata: None,
         }
     }
 
@@ -26,6 +35,16 @@ impl ItemStack {
             item_type,
             amount,
             creative_slot,
+            metadata: None,
+        }
+    }
+
+    /// Return the same items with another amount, outside of a creative slot. The metadata is kept.
+    pub fn with_amount(&self, amount: u8) -> Self {
+        ItemStack {
+            amount,
+            creative_slot: false,
+            ..self.clone()
         }
     }
 
@@ -40,9 +59,59 @@ impl ItemStack {
         }
     }
 
+    /// Return true if the two stacks hold the same items and can be merged
+    pub fn can_stack_with(&self, other: &ItemStack) -> bool {
+        self.item_type == other.item_type && self.metadata == other.metadata
+    }
+
+    /// Return the remaining uses of the tool, between 0 and 1. Return None if the item is not a tool.
+    pub fn get_durability(&self) -> Option<f32> {
+        let max_durability = self.item_type.get_max_durability()?;
+        let damage = self.metadata.as_ref().map_or(0, |metadata| metadata.damage);
+        Some(1. - damage as f32 / max_durability as f32)
+    }
+
     pub fn clear(&mut self) {
         self.amount = 0;
         self.item_type = ItemType::Air;
+        self.metadata = None;
+    }
+}
+
+/// An item stack of the worlds saved before the tools
+#[derive(Deserialize)]
+pub struct LegacyItemStack {
+    item_type: ItemType,
+    amount: u8,
+    creative_slot: bool,
+}
+
+impl From<LegacyItemStack> for ItemStack {
+    fn from(legacy: LegacyItemStack) -> Self {
+        ItemStack::new(legacy.item_type, legacy.amount, legacy.creative_slot)
+    }
+}
+
+/// An item stack of the worlds saved before the item metadata
+#[derive(Deserialize)]
+pub struct LegacyItemStackWithDamage {
+    item_type: ItemType,
+    amount: u8,
+    creative_slot: bool,
+    damage: u16,
+}
+
+impl From<LegacyItemStackWithDamage> for ItemStack {
+    fn from(legacy: LegacyItemStackWithDamage) -> Self {
+        ItemStack {
+            item_type: legacy.item_type,
+            amount: legacy.amount,
+            creative_slot: legacy.creative_slot,
+            // Undamaged tools have no metadata, so that they merge with the new ones
+            metadata: (legacy.damage > 0).then_some(ItemMetadata {
+                damage: legacy.damage,
+            }),
+        }
     }
 }
 
@@ -52,6 +121,22 @@ pub struct Inventory {
     pub modified: bool,
 }
 
+/// An inventory of the worlds saved with an older item stack format
+#[derive(Deserialize)]
+pub struct LegacyInventory<S> {
+    slots: Vec<S>,
+    modified: bool,
+}
+
+impl<S: Into<ItemStack>> From<LegacyInventory<S>> for Inventory {
+    fn from(legacy: LegacyInventory<S>) -> Self {
+        Inventory {
+            slots: legacy.slots.into_iter().map(Into::into).collect(),
+            modified: legacy.modified,
+        }
+    }
+}
+
 /// A generic inventory. Can be the player inventory, a chest inventory, etc...
 impl Inventory {
     pub fn new(size: usize) -> Self {
@@ -67,8 +152,9 @@ impl Inventory {
 
     pub fn fill(&mut self, item_stack: ItemStack) {
         for i in 0..self.slots.len() {
-            self.slots[i] = item_stack;
+            self.slots[i] = item_stack.clone();
         }
+        self.modified = true;
     }
 
     pub fn move_item_in_other_inventory(
@@ -93,20 +179,18 @@ impl Inventory {
         };
 
         if start_slot_itemstack.creative_slot && !end_slot_itemstack.creative_slot {
-            if end_slot_itemstack.item_type == start_slot_itemstack.item_type {
+            if end_slot_itemstack.can_stack_with(&start_slot_itemstack) {
                 other_inventory.replace_slot_item_stack(
                     end_slot,
-                    ItemStack::new(
-                        start_slot_itemstack.item_type,
+                    start_slot_itemstack.with_amount(
                         (end_slot_itemstack.amount as usize + selected_amount)
                             .min(start_max_stack_amount) as u8,
-                        false,
                     ),
                 );
             } else {
                 other_inventory.replace_slot_item_stack(
                     end_slot,
-                    ItemStack::new(start_slot_itemstack.item_type, selected_amount as u8, false),
+                    start_slot_itemstack.with_amount(selected_amount as u8),
                 );
             }
         } else if !start_slot_itemstack.creative_slot && end_slot_itemstack.creative_slot {
@@ -115,15 +199,12 @@ impl Inventory {
             } else {
                 self.replace_slot_item_stack(
                     start_slot,
-                    ItemStack::new(
-                        start_slot_itemstack.item_type,
-                        start_slot_itemstack.amount - selected_amount as u8,
-                        false,
-                    ),
+                    start_slot_itemstack
+                        .with_amount(start_slot_itemstack.amount - selected_amount as u8),
                 );
             }
         } else if !start_slot_itemstack.creative_slot && !end_slot_itemstack.creative_slot {
-            if start_slot_itemstack.get_item_type() == end_slot_itemstack.get_item_type()
+            if start_slot_itemstack.can_stack_with(&end_slot_itemstack)
                 && selected_amount != start_max_stack_amount
                 && end_slot_itemstack.amount as usize != end_max_stack_amount
             {
@@ -134,20 +215,17 @@ impl Inventory {
                         self.replace_slot_item_stack(start_slot, ItemStack::void());
                         other_inventory.replace_slot_item_stack(
                             end_slot,
-                            ItemStack::new(end_slot_itemstack.item_type, total_amount as u8, false),
+                            end_slot_itemstack.with_amount(total_amount as u8),
                         );
                     } else {
                         self.replace_slot_item_stack(
                             start_slot,
-                            ItemStack::new(
-                                start_slot_itemstack.item_type,
-                                start_slot_itemstack.amount - selected_amount as u8,
-                                false,
-                            ),
+                            start_slot_itemstack
+                                .with_amount(start_slot_itemstack.amount - selected_amount as u8),
                         );
                         other_inventory.replace_slot_item_stack(
                             end_slot,
-                            ItemStack::new(end_slot_itemstack.item_type, total_amount as u8, false),
+                            end_slot_itemstack.with_amount(total_amount as u8),
                         );
                     }
                 } else if total_amount == start_max_stack_amount {
@@ -155,46 +233,29 @@ impl Inventory {
                         self.replace_slot_item_stack(start_slot, ItemStack::void());
                         other_inventory.replace_slot_item_stack(
                             end_slot,
-                            ItemStack::new(
-                                end_slot_itemstack.item_type,
-                                start_max_stack_amount as u8,
-                                false,
-                            ),
+                            end_slot_itemstack.with_amount(start_max_stack_amount as u8),
                         );
                     } else {
                         self.replace_slot_item_stack(
                             start_slot,
-                            ItemStack::new(
-                                start_slot_itemstack.item_type,
+                            start_slot_itemstack.with_amount(
                                 start_slot_itemstack.get_amount() - selected_amount as u8,
-                                false,
                             ),
                         );
                         other_inventory.replace_slot_item_stack(
                             end_slot,
-                            ItemStack::new(
-                                end_slot_itemstack.item_type,
-                                start_max_stack_amount as u8,
-                                false,
-                            ),
+                            end_slot_itemstack.with_amount(start_max_stack_amount as u8),
                         );
                     }
                 } else {
                     self.replace_slot_item_stack(
                         start_slot,
-                        ItemStack::new(
-                            start_slot_itemstack.item_type,
-                            (total_amount - start_max_stack_amount) as u8,
-                            false,
-                        ),
+                        start_slot_itemstack
+                            .with_amount((total_amount - start_max_stack_amount) as u8),
                     );
                     other_inventory.replace_slot_item_stack(
                         end_slot,
-                        ItemStack::new(
-                            end_slot_itemstack.item_type,
-                            end_max_stack_amount as u8,
-                            false,
-                        ),
+                        end_slot_itemstack.with_amount(end_max_stack_amount as u8),
                     );
                 }
             } else {
@@ -204,19 +265,12 @@ impl Inventory {
                 {
                     other_inventory.replace_slot_item_stack(
                         end_slot,
-                        ItemStack::new(
-                            start_slot_itemstack.item_type,
-                            selected_amount as u8,
-                            false,
-                        ),
+                        start_slot_itemstack.with_amount(selected_amount as u8),
                     );
                     self.replace_slot_item_stack(
                         start_slot,
-                        ItemStack::new(
-                            start_slot_itemstack.item_type,
-                            start_slot_itemstack.get_amount() - selected_amount as u8,
-                            false,
-                        ),
+                        start_slot_itemstack
+                            .with_amount(start_slot_itemstack.get_amount() - selected_amount as u8),
                     );
                 } else {
                     other_inventory.replace_slot_item_stack(end_slot, start_slot_itemstack);
@@ -237,6 +291,45 @@ impl Inventory {
         Some(item_type)
     }
 
+    /// Remove up to `amount` items from the slot and return them. Creative slots are never emptied.
+    pub fn take_amount(&mut self, index: usize, amount: u8) -> Option<ItemStack> {
+        let slot = self.slots.get_mut(index)?;
+        if slot.item_type == ItemType::Air || amount == 0 {
+            return None;
+        }
+
+        let amount = amount.min(slot.get_amount());
+        let taken = slot.with_amount(amount);
+        if !slot.creative_slot {
+            slot.amount -= amount;
+            if slot.amount == 0 {
+                slot.clear();
+            }
+            self.modified = true;
+        }
+        Some(taken)
+    }
+
+    /// Wear the tool at the given slot. The tool breaks when it has no durability left.
+    pub fn damage_item(&mut self, index: usize) {
+        let Some(slot) = self.slots.get_mut(index) else {
+            return;
+        };
+        let Some(max_durability) = slot.item_type.get_max_durability() else {
+            return;
+        };
+        if slot.creative_slot {
+            return;
+        }
+
+        let metadata = slot.metadata.get_or_insert_default();
+        metadata.damage += 1;
+        if metadata.damage >= max_durability {
+            slot.clear();
+        }
+        self.modified = true;
+    }
+
     pub fn move_item(
         &mut self,
         start_slot: usize,
@@ -262,20 +355,18 @@ impl Inventory {
         };
 
         if start_slot_itemstack.creative_slot && !end_slot_itemstack.creative_slot {
-            if end_slot_itemstack.item_type == start_slot_itemstack.item_type {
+            if end_slot_itemstack.can_stack_with(&start_slot_itemstack) {
                 self.replace_slot_item_stack(
                     end_slot,
-                    ItemStack::new(
-                        start_slot_itemstack.item_type,
+                    start_slot_itemstack.with_amount(
                         (start_slot_itemstack.amount as usize + selected_amount)
                             .min(start_max_stack_amount) as u8,
-                        false,
                     ),
                 );
             } else {
                 self.replace_slot_item_stack(
                     end_slot,
-                    ItemStack::new(start_slot_itemstack.item_type, selected_amount as u8, false),
+                    start_slot_itemstack.with_amount(selected_amount as u8),
                 );
             }
         } else if !start_slot_itemstack.creative_slot && end_slot_itemstack.creative_slot {
@@ -284,15 +375,12 @@ impl Inventory {
             } else {
                 self.replace_slot_item_stack(
                     start_slot,
-                    ItemStack::new(
-                        start_slot_itemstack.item_type,
-                        start_slot_itemstack.amount - selected_amount as u8,
-                        false,
-                    ),
+                    start_slot_itemstack
+                        .with_amount(start_slot_itemstack.amount - selected_amount as u8),
                 );
             }
         } else if !start_slot_itemstack.creative_slot && !end_slot_itemstack.creative_slot {
-            if start_slot_itemstack.get_item_type() == end_slot_itemstack.get_item_type()
+            if start_slot_itemstack.can_stack_with(&end_slot_itemstack)
                 && selected_amount != start_max_stack_amount
                 && end_slot_itemstack.amount as usize != end_max_stack_amount
             {
@@ -303,20 +391,17 @@ impl Inventory {
                         self.replace_slot_item_stack(start_slot, ItemStack::void());
                         self.replace_slot_item_stack(
                             end_slot,
-                            ItemStack::new(end_slot_itemstack.item_type, total_amount as u8, false),
+                            end_slot_itemstack.with_amount(total_amount as u8),
                         );
                     } else {
                         self.replace_slot_item_stack(
                             start_slot,
-                            ItemStack::new(
-                                start_slot_itemstack.item_type,
-                                start_slot_itemstack.amount - selected_amount as u8,
-                                false,
-                            ),
+                            start_slot_itemstack
+                                .with_amount(start_slot_itemstack.amount - selected_amount as u8),
                         );
                         self.replace_slot_item_stack(
                             end_slot,
-                            ItemStack::new(end_slot_itemstack.item_type, total_amount as u8, false),
+                            end_slot_itemstack.with_amount(total_amount as u8),
                         );
                     }
                 } else if total_amount == start_max_stack_amount {
@@ -324,48 +409,32 @@ impl Inventory {
                         self.replace_slot_item_stack(start_slot, ItemStack::void());
                         self.replace_slot_item_stack(
                             end_slot,
-                            ItemStack::new(
-                                end_slot_itemstack.item_type,
-                                start_max_stack_amount as u8,
-                                false,
-                            ),
+                            end_slot_itemstack.with_amount(start_max_stack_amount as u8),
                         );
                     } else {
                         self.replace_slot_item_stack(
                             start_slot,
-                            ItemStack::new(
-                                start_slot_itemstack.item_type,
+                            start_slot_itemstack.with_amount(
                                 start_slot_itemstack.get_amount() - selected_amount as u8,
-                                false,
                             ),
                         );
                         self.replace_slot_item_stack(
                             end_slot,
-                            ItemStack::new(
-                                end_slot_itemstack.item_type,
-                                start_max_stack_amount as u8,
-                                false,
-                            ),
+                            end_slot_itemstack.with_amount(start_max_stack_amount as u8),
                         );
                     }
                 } else {
                     self.replace_slot_item_stack(
                         start_slot,
-                        ItemStack::new(
-                            start_slot_itemstack.item_type,
+                        start_slot_itemstack.with_amount(
                             (end_slot_itemstack.get_amount() as usize - start_max_stack_amount
                                 + start_slot_itemstack.get_amount() as usize)
                                 as u8,
-                            false,
                         ),
                     );
                     self.replace_slot_item_stack(
                         end_slot,
-                        ItemStack::new(
-                            end_slot_itemstack.item_type,
-                            end_max_stack_amount as u8,
-                            false,
-                        ),
+                        end_slot_itemstack.with_amount(end_max_stack_amount as u8),
                     );
                 }
             } else {
@@ -375,19 +444,12 @@ impl Inventory {
                 {
                     self.replace_slot_item_stack(
                         end_slot,
-                        ItemStack::new(
-                            start_slot_itemstack.item_type,
-                            selected_amount as u8,
-                            false,
-                        ),
+                        start_slot_itemstack.with_amount(selected_amount as u8),
                     );
                     self.replace_slot_item_stack(
                         start_slot,
-                        ItemStack::new(
-                            start_slot_itemstack.item_type,
-                            start_slot_itemstack.get_amount() - selected_amount as u8,
-                            false,
-                        ),
+                        start_slot_itemstack
+                            .with_amount(start_slot_itemstack.get_amount() - selected_amount as u8),
                     );
                 } else {
                     self.swap_slots(start_slot, end_slot);
@@ -474,20 +536,14 @@ impl Inventory {
 
         // Check incomplete stacks
         for i in 0..self.slots.len() {
-            if self.slots[i].get_item_type() == item_stack.get_item_type() {
+            if self.slots[i].can_stack_with(&item_stack) && self.slots[i].get_amount() < max_stack {
                 let total = self.slots[i].get_amount() as usize + amount;
 
                 if total <= max_stack as usize {
-                    self.replace_slot_item_stack(
-                        i,
-                        ItemStack::new(item_stack.get_item_type(), total as u8, false),
-                    );
+                    self.replace_slot_item_stack(i, item_stack.with_amount(total as u8));
                     return 0;
                 } else {
-                    self.replace_slot_item_stack(
-                        i,
-                        ItemStack::new(item_stack.get_item_type(), max_stack, false),
-                    );
+                    self.replace_slot_item_stack(i, item_stack.with_amount(max_stack));
                     amount = total - max_stack as usize;
                 }
             }
@@ -500,16 +556,10 @@ impl Inventory {
         for i in 0..self.slots.len() {
             if self.slots[i].get_item_type() == ItemType::Air {
                 if amount <= max_stack as usize {
-                    self.replace_slot_item_stack(
-                        i,
-                        ItemStack::new(item_stack.get_item_type(), amount as u8, false),
-                    );
+                    self.replace_slot_item_stack(i, item_stack.with_amount(amount as u8));
                     return 0;
                 } else {
-                    self.replace_slot_item_stack(
-                        i,
-                        ItemStack::new(item_stack.get_item_type(), max_stack, false),
-                    );
+                    self.replace_slot_item_stack(i, item_stack.with_amount(max_stack));
                     amount -= max_stack as usize;
                 }
             }
@@ -518,6 +568,42 @@ impl Inventory {
         return amount as u8;
     }
 
+    /// Merge the partial stacks of the given slots and order them by item type. The empty slots go last.
+    pub fn sort(&mut self, slots: Range<usize>) {
+        let mut sorted_stacks: Vec<ItemStack> = Vec::new();
+
+        for slot in slots.clone() {
+            let item_stack = &self.slots[slot];
+            if item_stack.item_type == ItemType::Air {
+                continue;
+            }
+
+            let max_stack = item_stack.item_type.get_max_stack_amount();
+            let mut amount = item_stack.amount;
+            for sorted_stack in sorted_stacks.iter_mut() {
+                if amount == 0 {
+                    break;
+                }
+                if sorted_stack.can_stack_with(item_stack) && sorted_stack.amount < max_stack {
+                    let moved = amount.min(max_stack - sorted_stack.amount);
+                    sorted_stack.amount += moved;
+                    amount -= moved;
+                }
+            }
+            if amount > 0 {
+                sorted_stacks.push(item_stack.with_amount(amount));
+            }
+        }
+
+        // The sort is stable so tools of the same type keep their order
+        sorted_stacks.sort_by_key(|item_stack| item_stack.item_type as u8);
+
+        for (i, slot) in slots.enumerate() {
+            let item_stack = sorted_stacks.get(i).cloned().unwrap_or(ItemStack::void());
+            self.replace_slot_item_stack(slot, item_stack);
+        }
+    }
+
     pub fn get_item_stack_at_slot_index(&self, index: usize) -> Option<&ItemStack> {
         self.slots.get(index)
     }
@@ -1,5 +1,5 @@
 #![cfg_attr(target_os = "none", no_std)]
-#![no_main]
+#![cfg_attr(not(test), no_main)]
 #![feature(const_index)]
 #![feature(const_trait_impl)]
 
@@ -30,6 +30,8 @@ setup_allocator!();
 
 configure_app!(b"Numcraft\0", 9, "../target/assets/icon.nwi", 3437);
 
+// The tests run on the host with the harness main
+#[cfg(not(test))]
 #[unsafe(no_mangle)]
 fn main() {
     init_heap!();
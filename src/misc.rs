@@ -1,3 +1,6 @@
+use rand_core::RngCore;
+use rand_xorshift::XorShiftRng;
+
 #[inline(always)]
 pub fn div_floor(a: isize, b: isize) -> isize {
     if a >= 0 
@@ -22,3 +25,8 @@ pub fn mod_floor(a: isize, b: isize) -> isize
         (a - b + 1) % b
     }
 }
+
+/// Return a random number between 0 and 1
+pub fn random_f32(rng: &mut XorShiftRng) -> f32 {
+    rng.next_u32() as f32 / u32::MAX as f32
+}
@@ -1,8 +1,19 @@
+calc_use!(alloc::vec::Vec);
+
+use libm::{ceilf, floorf};
 use nalgebra::Vector3;
 
 use crate::{
-    constants::physic::{BLOCK_COLLISION_SCANNING_SIZE, GRAVITY_FACTOR, ON_FLOOR_FRICTION},
-    world::World,
+    constants::{
+        EntityType, ItemType,
+        physic::{
+            BLOCK_COLLISION_SCANNING_SIZE, GRAVITY_FACTOR, MAX_STEP_HEIGHT, ON_FLOOR_FRICTION,
+        },
+        player::SAFE_FALL_DISTANCE,
+    },
+    entity::falling_block::FallingBlockData,
+    inventory::ItemStack,
+    world::{World, block_shape::BLOCK_BOXES},
 };
 
 #[derive(Clone, Debug)]
@@ -11,6 +22,15 @@ pub struct BoundingBox {
     pub size: Vector3<f32>,
 }
 
+/// Return the damage taken when landing after a fall of the given height, 1 per block after the safe distance
+pub fn get_fall_damage(fall_distance: f32) -> u8 {
+    if fall_distance > SAFE_FALL_DISTANCE {
+        ceilf(fall_distance - SAFE_FALL_DISTANCE) as u8
+    } else {
+        0
+    }
+}
+
 pub struct PhysicEngine {}
 
 impl PhysicEngine {
@@ -41,6 +61,18 @@ impl PhysicEngine {
             }
         }
 
+        // Place the falling blocks that reached the ground
+        let landed_blocks: Vec<usize> = world
+            .get_all_entities()
+            .iter()
+            .filter(|entity| entity.get_type() == EntityType::FallingBlock && entity.is_on_floor)
+            .map(|entity| entity.get_id())
+            .collect();
+
+        for id in landed_blocks {
+            self.land_falling_block(world, id);
+        }
+
         // Friction
         for entity in world.get_all_entities_mut().iter_mut() {
             if entity.velocity.norm() > 0. {
@@ -61,12 +93,79 @@ impl PhysicEngine {
         }
     }
 
+    /// Place the falling block back into the world or drop it as an item if its landing spot is occupied
+    fn land_falling_block(&self, world: &mut World, entity_id: usize) {
+        let Some(entity) = world.get_entity_by_id(entity_id) else {
+            return;
+        };
+        let Some(falling_block_data) = FallingBlockData::get_falling_block_data(entity) else {
+            return;
+        };
+
+        let block_type = falling_block_data.block_type;
+        let block_state = falling_block_data.block_state;
+        let entity_pos = entity.pos;
+        let block_pos = entity_pos.map(|v| floorf(v) as isize);
+
+        world.remove_entity(entity_id);
+
+        let block_bbox = BoundingBox {
+            offset: block_pos.map(|v| v as f32),
+            size: Vector3::repeat(1.0),
+        };
+
+        // Items on the ground don't prevent the block from being placed
+        let is_free = world
+            .chunks_manager
+            .get_block_in_world(block_pos)
+            .is_some_and(|b| b.is_air())
+            && !world.get_all_entities().iter().any(|entity| {
+                entity.get_type() != EntityType::Item
+                    && entity
+                        .get_bbox()
+                        .is_some_and(|bbox| bbox.is_coliding(&block_bbox))
+            });
+
+        if is_free {
+            world.set_block_in_world_with_state(block_pos, block_type, block_state);
+        } else {
+            let drop_type = block_type.get_dropped_item_type();
+            if drop_type != ItemType::Air {
+                world.spawn_item_entity(entity_pos, ItemStack::new(drop_type, 1, false));
+            }
+        }
+    }
+
     pub fn move_entity(&self, world: &mut World, entity_id: usize, movement: Vector3<f32>) {
         if let Some(state) = self.is_entity_colliding_world(entity_id, world, movement)
-            && let Some(entity) = world.get_entity_by_id_mut(entity_id)
+            && let Some(entity) = world.get_entity_by_id(entity_id)
         {
+            // Climb slabs and stairs when walking against them
+            if state && movement.y == 0. && entity.is_on_floor {
+                let step = Vector3::new(0., MAX_STEP_HEIGHT, 0.);
+                if self.is_entity_colliding_world(entity_id, world, step) == Some(false)
+                    && self.is_entity_colliding_world(entity_id, world, movement + step)
+                        == Some(false)
+                    && let Some(entity) = world.get_entity_by_id_mut(entity_id)
+                {
+                    entity.pos += movement + step;
+                    return;
+                }
+            }
+
+            let Some(entity) = world.get_entity_by_id_mut(entity_id) else {
+                return;
+            };
+
             if state {
                 if movement.y < 0. {
+                    // The height of the fall is deduced from the landing velocity
+                    let fall_distance =
+                        entity.velocity.y * entity.velocity.y / (2. * GRAVITY_FACTOR);
+                    let damage = get_fall_damage(fall_distance);
+                    if damage > 0 {
+                        entity.damage(damage);
+                    }
                     entity.is_on_floor = true;
                 }
 
@@ -109,14 +208,36 @@ impl PhysicEngine {
                     for bz in (entity_block_pos.z - BLOCK_COLLISION_SCANNING_SIZE.z)
                         ..=(entity_block_pos.z + BLOCK_COLLISION_SCANNING_SIZE.z)
                     {
-                        let block_or_none = world.chunks_manager.get_block_in_world(Vector3::new(bx, by, bz));
-                        if block_or_none.is_none() || block_or_none.is_some_and(|b| !b.is_air()) {
+                        let block_pos = Vector3::new(bx, by, bz);
+                        let moved_bbox = entity_bbox.transform(movement);
+
+                        if world
+                            .chunks_manager
+                            .get_block_in_world(block_pos)
+                            .is_some_and(|b| !b.has_collision())
+                        {
+                            continue;
+                        }
+
+                        if let Some(boxes) =
+                            world.chunks_manager.get_block_boxes_in_world(block_pos)
+                        {
+                            for box_id in boxes {
+                                if BLOCK_BOXES[box_id as usize]
+                                    .to_bounding_box(block_pos)
+                                    .is_coliding(&moved_bbox)
+                                {
+                                    return Some(true);
+                                }
+                            }
+                        } else {
+                            // Unloaded blocks are solid
                             let block_bbox = BoundingBox {
-                                offset: Vector3::new(bx as f32, by as f32, bz as f32),
+                                offset: block_pos.map(|v| v as f32),
                                 size: Vector3::repeat(1.0),
                             };
 
-                            if block_bbox.is_coliding(&entity_bbox.transform(movement)) {
+                            if block_bbox.is_coliding(&moved_bbox) {
                                 return Some(true);
                             }
                         }
@@ -153,6 +274,32 @@ impl BoundingBox {
             && self.offset.z + self.size.z > other.offset.z
     }
 
+    /// Return the distance along the ray where it enters the box. Return None if the ray misses the box.
+    pub fn ray_intersection(&self, origin: &Vector3<f32>, dir: &Vector3<f32>) -> Option<f32> {
+        let max = self.offset + self.size;
+
+        let mut t_enter = f32::NEG_INFINITY;
+        let mut t_exit = f32::INFINITY;
+
+        for axis in 0..3 {
+            if dir[axis] == 0. {
+                if origin[axis] < self.offset[axis] || origin[axis] > max[axis] {
+                    return None;
+                }
+                continue;
+            }
+            let t1 = (self.offset[axis] - origin[axis]) / dir[axis];
+            let t2 = (max[axis] - origin[axis]) / dir[axis];
+            t_enter = t_enter.max(t1.min(t2));
+            t_exit = t_exit.min(t1.max(t2));
+        }
+
+        if t_enter > t_exit || t_exit < 0. {
+            return None;
+        }
+        Some(t_enter.max(0.))
+    }
+
     pub fn transform(&self, vector: Vector3<f32>) -> BoundingBox {
         BoundingBox {
             offset: self.offset + vector,
@@ -160,3 +307,17 @@ impl BoundingBox {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fall_damage_starts_after_the_safe_distance() {
+        assert_eq!(get_fall_damage(SAFE_FALL_DISTANCE - 1.), 0);
+        assert_eq!(get_fall_damage(SAFE_FALL_DISTANCE), 0);
+        assert_eq!(get_fall_damage(SAFE_FALL_DISTANCE + 0.1), 1);
+        assert_eq!(get_fall_damage(SAFE_FALL_DISTANCE + 1.), 1);
+        assert_eq!(get_fall_damage(SAFE_FALL_DISTANCE + 1.5), 2);
+    }
+}
@@ -8,10 +8,17 @@ use nalgebra::{ComplexField, Vector3};
 use crate::{
     camera::Camera,
     constants::{
-        BlockType, EntityType,
-        player::{FLY_SPEED, JUMP_FORCE, MAX_WALKING_VELOCITY, WALK_FORCE},
+        BlockType, EntityType, ItemType,
+        player::{
+            ATTACK_EXHAUSTION, ATTACK_KNOCKBACK, ATTACK_KNOCKBACK_UP, ATTACK_REACH,
+            EATING_DURATION, ENVIRONMENT_DAMAGE_INTERVAL, EXHAUSTION_PER_HUNGER_POINT, FLY_SPEED,
+            HUNGER_EFFECTS_INTERVAL, ITEM_THROW_VELOCITY, JUMP_EXHAUSTION, JUMP_FORCE, MAX_HEALTH,
+            MAX_HUNGER, MAX_WALKING_VELOCITY, MINING_EXHAUSTION, MOVEMENT_EXHAUSTION,
+            REGENERATION_EXHAUSTION, REGENERATION_MIN_HUNGER, STARVATION_DAMAGE,
+            SUFFOCATION_DAMAGE, VOID_DAMAGE, VOID_HEIGHT, WALK_FORCE,
+        },
     },
-    entity::{Entity, item::ItemEntityCustomData},
+    entity::{Entity, EntityData, item::ItemEntityData},
     game::GameMode,
     hud::Hud,
     input_manager::InputManager,
@@ -20,25 +27,50 @@ use crate::{
     physic::PhysicEngine,
     renderer::mesh::{Mesh, Quad, QuadDir},
     settings::Settings,
-    world::World,
+    world::{
+        World,
+        block_shape::{BLOCK_BOXES, BlockShape, FULL_BOX},
+        block_state::BlockState,
+    },
 };
 
-calc_use!(alloc::boxed::Box);
-
 pub struct Player {
     ray_cast_result: Option<RaycastResult>,
+    /// Id of the entity in front of the player, within reach. Hides the targeted block.
+    targeted_entity: Option<usize>,
     pub inventory: Inventory,
     breaking_state_timer: f32,
+    /// Time needed to mine the targeted block with the selected item
+    breaking_duration: f32,
     breaking_block_pos: Option<Vector3<isize>>,
+    /// Time before the player takes damage again from suffocation or from the void
+    environment_damage_timer: f32,
+    /// Food level, in half food icons
+    pub hunger: u8,
+    /// Accumulated effort. Each EXHAUSTION_PER_HUNGER_POINT removes a hunger point.
+    exhaustion: f32,
+    /// Time before the next regeneration or starvation damage
+    hunger_effects_timer: f32,
+    /// Time spent eating the held food. None if the player is not eating.
+    eating_timer: Option<f32>,
+    pub opened_block_ui: Option<Vector3<isize>>,
 }
 
 impl Player {
     pub fn new() -> Self {
         Player {
             ray_cast_result: None,
+            targeted_entity: None,
             inventory: Inventory::new(24),
             breaking_state_timer: 0.,
+            breaking_duration: 0.,
             breaking_block_pos: None,
+            environment_damage_timer: 0.,
+            hunger: MAX_HUNGER,
+            exhaustion: 0.,
+            hunger_effects_timer: HUNGER_EFFECTS_INTERVAL,
+            eating_timer: None,
+            opened_block_ui: None,
         }
     }
 
@@ -46,8 +78,13 @@ impl Player {
         let mut mesh = Mesh::new();
 
         if let Some(result) = &self.ray_cast_result {
-            mesh.quads
-                .push(Quad::new(Vector3::new(0, 0, 0), result.face_dir, 255, 0));
+            mesh.quads.push(Quad::new(
+                Vector3::new(0, 0, 0),
+                result.face_dir,
+                255,
+                0,
+                result.box_id,
+            ));
             (mesh, result.block_pos)
         } else {
             (mesh, Vector3::repeat(0))
@@ -58,12 +95,24 @@ impl Player {
         if self.breaking_block_pos.is_none() {
             return None;
         }
-        let hardness = self.ray_cast_result.as_ref()?.block_type.get_hardness();
-        if hardness <= 0. {
+        if self.breaking_duration <= 0. {
             return None;
         }
 
-        Some((hardness - self.breaking_state_timer) / hardness)
+        Some((self.breaking_duration - self.breaking_state_timer) / self.breaking_duration)
+    }
+
+    /// Return the progress of the food being eaten, between 0 and 1
+    pub fn get_eating_progress(&self) -> Option<f32> {
+        self.eating_timer.map(|timer| timer / EATING_DURATION)
+    }
+
+    /// Fill the hunger bar, when the world is created or after a respawn
+    pub fn restore_hunger(&mut self) {
+        self.hunger = MAX_HUNGER;
+        self.exhaustion = 0.;
+        self.hunger_effects_timer = HUNGER_EFFECTS_INTERVAL;
+        self.eating_timer = None;
     }
 
     pub fn sync_with_camera(&self, camera: &mut Camera, player_entity: &mut Entity) {
@@ -75,6 +124,41 @@ impl Player {
         self.inventory = inventory
     }
 
+    /// Spawn all the items of the inventory as item entities at the player position, then empty the inventory
+    pub fn drop_inventory(&mut self, world: &mut World) {
+        let pos = world.get_player_entity().pos + Vector3::new(0., 0.5, 0.);
+        for item_stack in self.inventory.get_all_slots() {
+            if item_stack.get_item_type() != ItemType::Air {
+                world.spawn_item_entity(pos, item_stack.clone());
+            }
+        }
+        self.inventory.fill(ItemStack::void());
+        self.inventory.modified = true;
+    }
+
+    /// Throw items of the given slot forward. They can't be picked up again right away.
+    pub fn drop_items(
+        &mut self,
+        world: &mut World,
+        camera: &Camera,
+        slot_index: usize,
+        amount: u8,
+    ) {
+        if let Some(item_stack) = self.inventory.take_amount(slot_index, amount) {
+            Self::throw_item_stack(world, camera, item_stack);
+        }
+    }
+
+    /// Throw an item stack forward from the camera position
+    pub fn throw_item_stack(world: &mut World, camera: &Camera, item_stack: ItemStack) {
+        let pos = *camera.get_pos() - Vector3::new(0., 0.3, 0.);
+        world.throw_item_entity(
+            pos,
+            camera.get_forward_vector() * ITEM_THROW_VELOCITY,
+            item_stack,
+        );
+    }
+
     pub fn update(
         &mut self,
         delta: f32,
@@ -87,7 +171,13 @@ impl Player {
         delta_time: f32,
         settings: &Settings,
     ) {
-        self.ray_cast_result = Self::ray_cast(camera, world, 10.);
+        // Entities hide the blocks behind them
+        self.targeted_entity = Self::ray_cast_entity(camera, world, ATTACK_REACH);
+        self.ray_cast_result = if self.targeted_entity.is_some() {
+            None
+        } else {
+            Self::ray_cast(camera, world, 10.)
+        };
 
         let player_entity = world.get_player_entity_mut();
 
@@ -155,6 +245,7 @@ impl Player {
                 player_entity.pos.y += delta * FLY_SPEED;
             } else if player_entity.is_on_floor {
                 player_entity.velocity.y += JUMP_FORCE;
+                self.exhaustion += JUMP_EXHAUSTION;
             }
         }
         if input_manager.is_keydown(nadk::keyboard::Key::Exp) {
@@ -171,25 +262,53 @@ impl Player {
             player_entity.velocity.z = max_velocity.y;
         }
 
+        if game_mode == GameMode::Survival {
+            self.exhaustion +=
+                player_entity.velocity.xz().norm() * delta_time * MOVEMENT_EXHAUSTION;
+            self.update_environment_damage(world, camera, delta_time);
+        }
+
+        // Hit the targeted entity
+        if let Some(entity_id) = self.targeted_entity
+            && input_manager.is_just_pressed(nadk::keyboard::Key::Back)
+        {
+            self.attack_entity(world, camera, entity_id, hud.selected_slot, game_mode);
+        }
+
         // Break Block
         if game_mode == GameMode::Creative {
             if input_manager.is_just_pressed(nadk::keyboard::Key::Back) {
                 if let Some(result) = &self.ray_cast_result {
-                    world
-                        .chunks_manager
-                        .set_block_in_world(result.block_pos, BlockType::Air);
+                    world.set_block_in_world(result.block_pos, BlockType::Air);
                 }
             }
         } else {
             if input_manager.is_keydown(nadk::keyboard::Key::Back) {
                 if let Some(ray_cast) = &self.ray_cast_result {
+                    let held_item = self
+                        .inventory
+                        .get_item_type_at_slot_index(hud.selected_slot)
+                        .unwrap_or(ItemType::Air);
+
                     if self
                         .breaking_block_pos
                         .is_some_and(|pos| pos == ray_cast.block_pos)
                     {
                         self.breaking_state_timer -= delta_time;
                         if self.breaking_state_timer <= 0. {
-                            world.replace_block_and_drop_item(ray_cast.block_pos, BlockType::Air);
+                            // Blocks mined without the right tool don't drop anything
+                            if ray_cast.block_type.can_be_harvested_with(held_item) {
+                                world.replace_block_and_drop_item(
+                                    ray_cast.block_pos,
+                                    BlockType::Air,
+                                );
+                            } else {
+                                world.set_block_in_world(ray_cast.block_pos, BlockType::Air);
+                            }
+                            if ray_cast.block_type.get_hardness() > 0. {
+                                self.inventory.damage_item(hud.selected_slot);
+                            }
+                            self.exhaustion += MINING_EXHAUSTION;
                             self.breaking_block_pos = None;
                             self.breaking_state_timer = 0.;
                         }
@@ -197,7 +316,8 @@ impl Player {
                         let hardness = ray_cast.block_type.get_hardness();
                         if hardness >= 0. {
                             self.breaking_block_pos = Some(ray_cast.block_pos);
-                            self.breaking_state_timer = ray_cast.block_type.get_hardness();
+                            self.breaking_duration = ray_cast.block_type.get_mining_time(held_item);
+                            self.breaking_state_timer = self.breaking_duration;
                         }
                     }
                 } else {
@@ -211,45 +331,66 @@ impl Player {
         }
 
         if input_manager.is_just_pressed(nadk::keyboard::Key::Ok) {
-            // Place Block
-            if let Some(result) = &self.ray_cast_result {
-                let block_pos = result.block_pos + result.face_dir.get_normal_vector();
-                if world
-                    .chunks_manager
-                    .get_block_in_world(block_pos)
-                    .is_some_and(|b| b.is_air())
-                    && physic_engine.can_place_block(world, block_pos)
-                    && let Some(item_type) = self.inventory.take_one(0 + hud.selected_slot)
-                    && let Some(block_type) = item_type.get_matching_block_type()
-                {
-                    world
-                        .chunks_manager
-                        .set_block_in_world(block_pos, block_type);
-                }
+            // Open the block interface, interact with the block or place a block
+            if let Some(result) = &self.ray_cast_result
+                && result.block_type.has_ui()
+            {
+                self.opened_block_ui = Some(result.block_pos);
+            } else if let Some(result) = &self.ray_cast_result
+                && world.interact_with_block(result.block_pos)
+            {
+                // The block reacted to the interaction
+            } else if !self.place_held_block(world, camera, hud.selected_slot, physic_engine)
+                && game_mode == GameMode::Survival
+                && self
+                    .inventory
+                    .get_item_type_at_slot_index(hud.selected_slot)
+                    .and_then(|item_type| item_type.get_food_value())
+                    .is_some()
+            {
+                // Food that can't be planted here is eaten
+                self.eating_timer = Some(0.);
             }
         }
 
+        // Drop one item, or the whole stack with [alpha]
+        if input_manager.is_just_pressed(nadk::keyboard::Key::Backspace) {
+            let amount = if input_manager.is_keydown(nadk::keyboard::Key::Alpha) {
+                u8::MAX
+            } else {
+                1
+            };
+            self.drop_items(world, camera, hud.selected_slot, amount);
+        }
+
+        if game_mode == GameMode::Survival {
+            self.update_eating(input_manager, hud.selected_slot, delta_time);
+            self.update_hunger(world, delta_time);
+        }
+
         let player_entity = world.get_player_entity();
 
         if let Some(player_bbox) = player_entity.get_bbox() {
             world.get_all_entities_mut().retain_mut(|entity| {
                 if let EntityType::Item { .. } = entity.get_type()
+                    && ItemEntityData::can_be_picked_up(entity)
                     && entity
                         .get_bbox()
                         .is_some_and(|entity_bbox| entity_bbox.is_coliding(&player_bbox))
                 {
                     // Recover the item_stack data from the item entity
-                    let item_data = ItemEntityCustomData::get_item_data(&entity)
-                        .expect("Item Entity must have ItemData as custom data.");
+                    let item_data = ItemEntityData::get_item_data(&entity)
+                        .expect("Item Entity must have ItemEntityData as data.");
 
-                    let item_stack = item_data.item_stack;
+                    let item_stack = item_data.item_stack.clone();
 
                     let remain = self.inventory.add_item_stack(item_stack.clone());
 
                     if remain != 0 {
-                        entity.custom_data = Some(Box::new(ItemEntityCustomData {
-                            item_stack: ItemStack::new(item_stack.get_item_type(), remain, false),
-                        }));
+                        entity.data = EntityData::Item(ItemEntityData {
+                            item_stack: item_stack.with_amount(remain),
+                            pickup_delay: 0.,
+                        });
                         return true;
                     }
                     false
@@ -260,6 +401,179 @@ impl Player {
         }
     }
 
+    /// Place the block matching the held item against the targeted face. Return true if the block has been placed.
+    fn place_held_block(
+        &mut self,
+        world: &mut World,
+        camera: &Camera,
+        slot_index: usize,
+        physic_engine: &PhysicEngine,
+    ) -> bool {
+        let Some(result) = &self.ray_cast_result else {
+            return false;
+        };
+
+        let block_pos = result.block_pos + result.face_dir.get_normal_vector();
+        if world
+            .chunks_manager
+            .get_block_in_world(block_pos)
+            .is_some_and(|b| b.is_air())
+            && physic_engine.can_place_block(world, block_pos)
+            && let Some(item_type) = self.inventory.get_item_type_at_slot_index(slot_index)
+            && let Some(block_type) = item_type.get_matching_block_type()
+            && world
+                .chunks_manager
+                .get_block_in_world(block_pos - Vector3::new(0, 1, 0))
+                .is_some_and(|below| block_type.can_be_placed_on(below))
+            && self.inventory.take_one(slot_index).is_some()
+        {
+            let block_state = BlockState::get_placement_state(
+                block_type,
+                result.face_dir,
+                camera.get_forward_vector(),
+            );
+            world.set_block_in_world_with_state(block_pos, block_type, block_state);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Eat the held food while [OK] is held. Stop when the player is full.
+    fn update_eating(&mut self, input_manager: &InputManager, slot_index: usize, delta_time: f32) {
+        let Some(eating_timer) = &mut self.eating_timer else {
+            return;
+        };
+
+        let food_value = match self
+            .inventory
+            .get_item_type_at_slot_index(slot_index)
+            .and_then(|item_type| item_type.get_food_value())
+        {
+            Some(food_value)
+                if input_manager.is_keydown(nadk::keyboard::Key::Ok)
+                    && self.hunger < MAX_HUNGER =>
+            {
+                food_value
+            }
+            _ => {
+                self.eating_timer = None;
+                return;
+            }
+        };
+
+        *eating_timer += delta_time;
+        if *eating_timer >= EATING_DURATION {
+            self.inventory.take_one(slot_index);
+            self.hunger = (self.hunger + food_value).min(MAX_HUNGER);
+            // Keep eating while the key is held
+            self.eating_timer = Some(0.);
+        }
+    }
+
+    /// Turn the exhaustion into hunger, then regenerate or starve the player
+    fn update_hunger(&mut self, world: &mut World, delta_time: f32) {
+        while self.exhaustion >= EXHAUSTION_PER_HUNGER_POINT {
+            self.exhaustion -= EXHAUSTION_PER_HUNGER_POINT;
+            self.hunger = self.hunger.saturating_sub(1);
+        }
+
+        self.hunger_effects_timer -= delta_time;
+        if self.hunger_effects_timer > 0. {
+            return;
+        }
+        self.hunger_effects_timer = HUNGER_EFFECTS_INTERVAL;
+
+        let player_entity = world.get_player_entity_mut();
+        if self.hunger >= REGENERATION_MIN_HUNGER
+            && player_entity
+                .health
+                .is_some_and(|health| health < MAX_HEALTH)
+        {
+            player_entity.heal(1);
+            self.exhaustion += REGENERATION_EXHAUSTION;
+        } else if self.hunger == 0 {
+            player_entity.damage(STARVATION_DAMAGE);
+        }
+    }
+
+    /// Hurt the player when its head is inside a block or when it fell in the void
+    fn update_environment_damage(&mut self, world: &mut World, camera: &Camera, delta_time: f32) {
+        let head_pos = camera.get_pos().map(|v| floorf(v) as isize);
+        let damage = if world.get_player_entity().pos.y < VOID_HEIGHT {
+            VOID_DAMAGE
+        } else if world
+            .chunks_manager
+            .get_block_in_world(head_pos)
+            .is_some_and(|block| block.is_full_cube())
+        {
+            SUFFOCATION_DAMAGE
+        } else {
+            self.environment_damage_timer = 0.;
+            return;
+        };
+
+        self.environment_damage_timer -= delta_time;
+        if self.environment_damage_timer <= 0. {
+            world.get_player_entity_mut().damage(damage);
+            self.environment_damage_timer = ENVIRONMENT_DAMAGE_INTERVAL;
+        }
+    }
+
+    /// Hurt the entity with the held item and push it away from the player
+    fn attack_entity(
+        &mut self,
+        world: &mut World,
+        camera: &Camera,
+        entity_id: usize,
+        slot_index: usize,
+        game_mode: GameMode,
+    ) {
+        let held_item = self
+            .inventory
+            .get_item_type_at_slot_index(slot_index)
+            .unwrap_or(ItemType::Air);
+
+        let forward = camera.get_forward_vector();
+        let mut knockback = Vector3::new(forward.x, 0., forward.z);
+        if knockback.norm() > 0. {
+            knockback = knockback.normalize() * ATTACK_KNOCKBACK;
+        }
+        knockback.y = ATTACK_KNOCKBACK_UP;
+
+        let Some(entity) = world.get_entity_by_id_mut(entity_id) else {
+            return;
+        };
+        entity.hit(held_item.get_attack_damage(), knockback);
+
+        if game_mode == GameMode::Survival {
+            self.inventory.damage_item(slot_index);
+            self.exhaustion += ATTACK_EXHAUSTION;
+        }
+    }
+
+    /// Return the id of the nearest entity that can be hit in front of the camera. Blocks stop the ray.
+    fn ray_cast_entity(camera: &Camera, world: &World, max_lenght: f32) -> Option<usize> {
+        let cam_pos = camera.get_pos();
+        let dir = camera.get_forward_vector();
+
+        let (id, distance) = world
+            .get_all_entities()
+            .iter()
+            .filter(|entity| entity.get_type() != EntityType::Player && entity.health.is_some())
+            .filter_map(|entity| {
+                let distance = entity.get_bbox()?.ray_intersection(cam_pos, &dir)?;
+                Some((entity.get_id(), distance))
+            })
+            .filter(|(_, distance)| *distance <= max_lenght)
+            .min_by(|a, b| a.1.total_cmp(&b.1))?;
+
+        if Self::ray_cast(camera, world, distance).is_some() {
+            return None;
+        }
+        Some(id)
+    }
+
     fn ray_cast(camera: &Camera, world: &World, max_lenght: f32) -> Option<RaycastResult> {
         let cam_pos = camera.get_pos();
         let dir = camera.get_forward_vector();
@@ -326,7 +640,34 @@ impl Player {
                 step_dir = 2;
             }
 
-            if world
+            if let Some(block_type) = world.chunks_manager.get_block_in_world(v_map_check)
+                && block_type.get_shape() != BlockShape::Cube
+            {
+                // Non-cubic blocks are only hit if the ray goes through one of their boxes
+                let mut nearest_hit: Option<(f32, QuadDir, u8)> = None;
+
+                for box_id in world
+                    .chunks_manager
+                    .get_block_boxes_in_world(v_map_check)
+                    .unwrap_or_default()
+                {
+                    if let Some((distance, face_dir)) =
+                        BLOCK_BOXES[box_id as usize].ray_intersection(v_map_check, cam_pos, &dir)
+                        && nearest_hit.is_none_or(|hit| distance < hit.0)
+                    {
+                        nearest_hit = Some((distance, face_dir, box_id));
+                    }
+                }
+
+                if let Some((_, face_dir, box_id)) = nearest_hit {
+                    return Some(RaycastResult {
+                        block_pos: v_map_check,
+                        face_dir,
+                        block_type,
+                        box_id,
+                    });
+                }
+            } else if world
                 .chunks_manager
                 .get_block_in_world(v_map_check)
                 .is_some_and(|block| !block.is_air())
@@ -355,6 +696,7 @@ impl Player {
                         .chunks_manager
                         .get_block_in_world(v_map_check)
                         .unwrap_or(BlockType::Air),
+                    box_id: FULL_BOX,
                 });
             }
         }
@@ -368,4 +710,5 @@ struct RaycastResult {
     pub block_pos: Vector3<isize>,
     pub face_dir: QuadDir,
     pub block_type: BlockType,
+    pub box_id: u8,
 }
@@ -7,7 +7,7 @@ use core::{cmp::Ordering, f32, mem::swap};
 
 use crate::{
     camera::Camera,
-    constants::{rendering::*, world::CHUNK_SIZE},
+    constants::{ItemTexture, rendering::*, world::CHUNK_SIZE},
     nadk::display::{COLOR_BLACK, Color565},
     renderer::mesh::SmallTriangle2D,
 };
@@ -54,6 +54,18 @@ const FONT_CHAR_WIDTH: usize = 11;
 static FONT_ORDER: &str = "!\" $%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^+`abcdefghijklmnopqrstuvwxyz{|}~€";
 
 static TILESET_DATA: &[u8] = include_bytes!("../target/assets/tileset.bin");
+static ITEMS_DATA: &[u8] = include_bytes!("../target/assets/items.bin");
+const ATLAS_WIDTH: usize = 128;
+const TRANSPARENT_COLOR: u16 = 0xF81F; // Color of the transparent pixels of the atlases
+
+/// Return the atlas holding the item texture and the position of the texture in the atlas
+fn get_item_texture_in_atlas(texture: ItemTexture) -> (&'static [u8], usize, usize) {
+    let (atlas, id) = match texture {
+        ItemTexture::Tile(id) => (TILESET_DATA, id),
+        ItemTexture::Sprite(id) => (ITEMS_DATA, id),
+    };
+    (atlas, (id % 16) as usize * 8, (id / 16) as usize * 8)
+}
 
 pub struct Renderer {
     pub camera: Camera,
@@ -501,6 +501,18 @@ impl Renderer {
         self.add_3d_triangle_to_render(quad_triangles.1, mat_view);
     }
 
+    /// Same as add_quad_to_render for the quads of the entities that look like blocks
+    fn add_block_entity_quad_to_render(
+        &mut self,
+        quad: &Quad,
+        mat_view: &Matrix4<f32>,
+        block_pos: Vector3<f32>,
+    ) {
+        let quad_triangles = quad.get_triangles_at(block_pos);
+        self.add_3d_triangle_to_render(quad_triangles.0, mat_view);
+        self.add_3d_triangle_to_render(quad_triangles.1, mat_view);
+    }
+
     pub fn draw_game(
         &mut self,
         world: &mut World,
@@ -519,6 +531,8 @@ impl Renderer {
             ZFAR,
         );
 
+        let block_entities = self.get_block_entities_meshes(world, &frustum);
+
         // Add the player block marker
         let mut block_marker = player.get_block_marker();
         for quad in block_marker.0.get_reference_vec() {
@@ -540,23 +554,28 @@ impl Renderer {
 
             let need_sorting = chunk.need_sorting || self.camera.get_has_moved();
 
+            // Falling blocks and primed TNT are sorted with the quads of the chunk they are in, nearest first
+            let camera_pos = *self.camera.get_pos();
+            let mut entity_quads: Vec<(f32, Vector3<f32>, &Quad)> = block_entities
+                .iter()
+                .filter(|(chunk_pos, _, _)| chunk_pos == chunk.get_pos())
+                .flat_map(|(_, block_pos, mesh)| {
+                    mesh.quads.iter().map(move |quad| {
+                        let distance = (quad.get_center() + block_pos).metric_distance(&camera_pos);
+                        (distance, *block_pos, quad)
+                    })
+                })
+                .collect();
+            entity_quads.sort_by(|a, b| a.0.total_cmp(&b.0));
+            let mut entity_quads = entity_quads.into_iter().peekable();
+
             let quads = chunk.get_mesh().get_reference_vec();
 
             if need_sorting {
                 quads.sort_by(|a, b| -> Ordering {
-                    let a_pos = a.get_pos().map(|x| x as isize) + chunk_blocks_pos;
-                    let b_pos = b.get_pos().map(|x| x as isize) + chunk_blocks_pos;
-                    let avec = Vector3::new(
-                        a_pos.x as f32 + 0.5,
-                        a_pos.y as f32 + 0.5,
-                        a_pos.z as f32 + 0.5,
-                    );
-
-                    let bvec = Vector3::new(
-                        b_pos.x as f32 + 0.5,
-                        b_pos.y as f32 + 0.5,
-                        b_pos.z as f32 + 0.5,
-                    );
+                    // Sort using the center of the box of the quad to handle non-cubic blocks
+                    let avec = a.get_center() + chunk_blocks_posf;
+                    let bvec = b.get_center() + chunk_blocks_posf;
 
                     bvec.metric_distance(self.camera.get_pos())
                         .total_cmp(&avec.metric_distance(self.camera.get_pos()))
@@ -564,8 +583,22 @@ impl Renderer {
                 });
             }
             for quad in quads {
+                if entity_quads.peek().is_some() {
+                    let distance =
+                        (quad.get_center() + chunk_blocks_posf).metric_distance(&camera_pos);
+                    while let Some((_, block_pos, entity_quad)) =
+                        entity_quads.next_if(|(entity_distance, _, _)| *entity_distance <= distance)
+                    {
+                        self.add_block_entity_quad_to_render(entity_quad, &mat_view, block_pos);
+                    }
+                }
                 self.add_quad_to_render(quad, &mat_view, chunk_blocks_pos);
             }
+
+            // The block entities behind all the blocks of the chunk
+            for (_, block_pos, entity_quad) in entity_quads {
+                self.add_block_entity_quad_to_render(entity_quad, &mat_view, block_pos);
+            }
         }
 
         for x in 0..SCREEN_TILE_SUBDIVISION {
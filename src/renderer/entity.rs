@@ -1,13 +1,54 @@
-use libm::tanf;
+use libm::{floorf, tanf};
 
 use crate::{
-    constants::EntityType,
-    entity::item::ItemEntityCustomData,
-    renderer::{frustum::Frustum, *},
-    world::World,
+    constants::{BlockType, EntityType},
+    entity::{falling_block::FallingBlockData, item::ItemEntityData},
+    renderer::{frustum::Frustum, mesh::Mesh, *},
+    world::{World, block_state::BlockState},
 };
 
 impl Renderer {
+    /// Return the meshes of the visible entities that look like blocks with the position of their chunk and their position in world
+    pub fn get_block_entities_meshes(
+        &self,
+        world: &World,
+        frustum: &Frustum,
+    ) -> Vec<(Vector3<isize>, Vector3<f32>, Mesh)> {
+        let mut meshes = Vec::new();
+
+        for entity in world.get_all_entities() {
+            if !matches!(
+                entity.get_type(),
+                EntityType::FallingBlock | EntityType::PrimedTnt
+            ) || !entity
+                .get_bbox()
+                .is_some_and(|bbox| frustum.is_bbox_in_frustum(&bbox))
+                || entity.pos.metric_distance(self.camera.get_pos()) > MAX_ENTITY_RENDER_DISTANCE
+            {
+                continue;
+            }
+
+            let mesh = if entity.get_type() == EntityType::PrimedTnt {
+                Mesh::generate_block(BlockType::Tnt, BlockState::DEFAULT)
+            } else if let Some(falling_block_data) =
+                FallingBlockData::get_falling_block_data(entity)
+            {
+                Mesh::generate_block(
+                    falling_block_data.block_type,
+                    falling_block_data.block_state,
+                )
+            } else {
+                continue;
+            };
+
+            let chunk_pos = entity.pos.map(|v| floorf(v / CHUNK_SIZE as f32) as isize);
+
+            meshes.push((chunk_pos, entity.pos - Vector3::repeat(0.5), mesh));
+        }
+
+        meshes
+    }
+
     pub fn draw_flat_model_entities(
         &mut self,
         world: &World,
@@ -25,55 +66,71 @@ impl Renderer {
                 continue;
             }
 
-            if let EntityType::Item { .. } = entity.get_type() {
-                // Extract the custom data of the entity
-                let item_data = ItemEntityCustomData::get_item_data(&entity)
-                    .expect("Item Entity must have ItemData as custom data.");
+            // Items and mobs are drawn as sprites facing the camera
+            let (texture, default_size, pos) =
+                if let Some(behavior) = entity.get_type().get_mob_behavior() {
+                    let Some(bbox) = entity.get_bbox() else {
+                        continue;
+                    };
+                    // Mobs are taller than wide, the sprite covers the bounding box height
+                    (
+                        behavior.get_texture(),
+                        bbox.size.y,
+                        bbox.offset + bbox.size / 2.,
+                    )
+                } else if let EntityType::Item { .. } = entity.get_type() {
+                    // Extract the item data of the entity
+                    let item_data = ItemEntityData::get_item_data(&entity)
+                        .expect("Item Entity must have ItemEntityData as data.");
 
-                let texture_id = item_data.item_stack.get_item_type().get_texture_id();
+                    let Some(texture) = item_data.item_stack.get_item_type().get_texture() else {
+                        continue;
+                    };
+                    (texture, ITEM_ENTITY_SPRITE_SIZE, entity.pos)
+                } else {
+                    continue;
+                };
 
-                // Transform and project the point
-                let pos = entity.pos;
-                let transformed = (mat_view * Vector4::new(pos.x, pos.y, pos.z, 1.0)).xyz();
-                let projected = (self.project_point(transformed) + Vector2::new(1., 1.))
-                    .component_mul(&HALF_SCREEN);
+            // Transform and project the point
+            let transformed = (mat_view * Vector4::new(pos.x, pos.y, pos.z, 1.0)).xyz();
+            let projected = (self.project_point(transformed) + Vector2::new(1., 1.))
+                .component_mul(&HALF_SCREEN);
 
-                let tile_offset = Vector2::new(
-                    -((SCREEN_TILE_WIDTH * tile_x) as isize),
-                    -((SCREEN_TILE_HEIGHT * tile_y) as isize),
-                );
+            let tile_offset = Vector2::new(
+                -((SCREEN_TILE_WIDTH * tile_x) as isize),
+                -((SCREEN_TILE_HEIGHT * tile_y) as isize),
+            );
 
-                let default_size = ITEM_ENTITY_SPRITE_SIZE;
-                let sprite_size: isize =
-                    ((default_size / self.camera.get_pos().metric_distance(&pos))
-                        * (SCREEN_HEIGHTF / tanf(2.0 * (FOV / 2.0)))) as isize;
-
-                let point = projected.map(|v| v as isize) + tile_offset;
-
-                if true
-                    || (point.x > -sprite_size / 2
-                        && point.y > -sprite_size / 2
-                        && point.x < SCREEN_TILE_WIDTH as isize + sprite_size / 2
-                        && point.y < SCREEN_TILE_HEIGHT as isize + sprite_size / 2)
-                {
-                    self.draw_set_size_tile_on_frame_buffer(
-                        texture_id,
-                        point.map(|v| v - sprite_size / 2),
-                        Vector2::repeat(sprite_size),
-                    );
-                }
+            let sprite_size: isize = ((default_size / self.camera.get_pos().metric_distance(&pos))
+                * (SCREEN_HEIGHTF / tanf(2.0 * (FOV / 2.0))))
+                as isize;
+
+            let point = projected.map(|v| v as isize) + tile_offset;
+
+            if true
+                || (point.x > -sprite_size / 2
+                    && point.y > -sprite_size / 2
+                    && point.x < SCREEN_TILE_WIDTH as isize + sprite_size / 2
+                    && point.y < SCREEN_TILE_HEIGHT as isize + sprite_size / 2)
+            {
+                self.draw_set_size_item_on_frame_buffer(
+                    texture,
+                    point.map(|v| v - sprite_size / 2),
+                    Vector2::repeat(sprite_size),
+                    entity.is_hurt(),
+                );
             }
         }
     }
 
-    fn draw_set_size_tile_on_frame_buffer(
+    fn draw_set_size_item_on_frame_buffer(
         &mut self,
-        texture_id: u8,
+        texture: ItemTexture,
         pos: Vector2<isize>,
         size: Vector2<isize>,
+        is_hurt: bool,
     ) {
-        let tileset_x = (texture_id % 16) as usize * 8;
-        let tileset_y = (texture_id / 16) as usize * 8;
+        let (atlas, atlas_x, atlas_y) = get_item_texture_in_atlas(texture);
 
         if pos.x + size.x <= 0 || pos.y + size.y <= 0 {
             return;
@@ -93,14 +150,24 @@ impl Renderer {
                 }
 
                 let texture_pixel_index =
-                    ((tileset_x + u as usize) + (tileset_y + v as usize) * 128) * 2;
+                    ((atlas_x + u as usize) + (atlas_y + v as usize) * ATLAS_WIDTH) * 2;
                 let pixel = u16::from_be_bytes([
-                    TILESET_DATA[texture_pixel_index],
-                    TILESET_DATA[texture_pixel_index + 1],
+                    atlas[texture_pixel_index],
+                    atlas[texture_pixel_index + 1],
                 ]);
+                if pixel == TRANSPARENT_COLOR {
+                    continue;
+                }
+
+                let mut color = Color565 { value: pixel };
+                if is_hurt {
+                    // Hurt entities flash in red
+                    let (_, g, b) = color.get_components();
+                    color = Color565::new(0b11111, g / 2, b / 2);
+                }
 
                 self.tile_frame_buffer[dest_x as usize + dest_y as usize * SCREEN_TILE_WIDTH] =
-                    Color565 { value: pixel };
+                    color;
             }
         }
     }
@@ -1,10 +1,38 @@
 use crate::{
-    constants::{ItemType, color_palette::GAMEUI_SLOT_COLOR},
+    constants::{
+        ItemType,
+        color_palette::GAMEUI_SLOT_COLOR,
+        player::{MAX_HEALTH, MAX_HUNGER},
+    },
     nadk::display::ScreenRect,
     hud::Hud,
     renderer::{misc::UnBoundedScreenRect, *},
 };
 
+/// The shapes of the health and hunger icons, one bit per pixel. The left half is made of the 5 highest bits.
+const HEART_SHAPE: [u16; 8] = [
+    0b011000110,
+    0b111101111,
+    0b111111111,
+    0b111111111,
+    0b011111110,
+    0b001111100,
+    0b000111000,
+    0b000010000,
+];
+const FOOD_SHAPE: [u16; 8] = [
+    0b000001110,
+    0b000011111,
+    0b000111111,
+    0b000111111,
+    0b001111110,
+    0b011011100,
+    0b110000000,
+    0b011000000,
+];
+const ICON_WIDTH: isize = 9;
+const ICON_SPACING: isize = 10;
+
 impl Renderer {
     pub fn draw_hud(&mut self, hud: &Hud, frame_time: u64, tile_x: usize, tile_y: usize) {
         if tile_x == 0 && tile_y == 0 {
@@ -87,9 +115,77 @@ impl Renderer {
             self.draw_slot_frame_buffer(Vector2::new(-10, 20), hud, 5);
         }
 
+        // The hearts are above the left half of the hotbar, from x = 60 to x = 160
+        if tile_x <= 1
+            && tile_y == 3
+            && let Some(health) = hud.health
+        {
+            self.draw_icon_bar(
+                Vector2::new(60 - (tile_x * SCREEN_TILE_WIDTH) as isize, 9),
+                &HEART_SHAPE,
+                health,
+                MAX_HEALTH,
+                Color565::from_rgb888(220, 30, 30),
+                Color565::from_rgb888(60, 20, 20),
+            );
+        }
+
+        // The food icons are above the right half of the hotbar, from x = 161 to x = 260
+        if (2..=3).contains(&tile_x)
+            && tile_y == 3
+            && let Some(hunger) = hud.hunger
+        {
+            self.draw_icon_bar(
+                Vector2::new(161 - (tile_x * SCREEN_TILE_WIDTH) as isize, 9),
+                &FOOD_SHAPE,
+                hunger,
+                MAX_HUNGER,
+                Color565::from_rgb888(200, 120, 50),
+                Color565::from_rgb888(60, 40, 20),
+            );
+        }
+
         self.draw_breaking_indicator(tile_x, tile_y, hud);
     }
 
+    /// Draw a row of icons showing a value, like the health of the player. Each icon is 2 points.
+    fn draw_icon_bar(
+        &mut self,
+        pos: Vector2<isize>,
+        shape: &[u16; 8],
+        value: u8,
+        max_value: u8,
+        full_color: Color565,
+        empty_color: Color565,
+    ) {
+        for icon in 0..(max_value / 2) {
+            let icon_x = pos.x + icon as isize * ICON_SPACING;
+            for (y, row) in shape.iter().enumerate() {
+                for x in 0..ICON_WIDTH {
+                    if row >> (ICON_WIDTH - 1 - x) & 1 == 0 {
+                        continue;
+                    }
+                    // The left half of the icon is the first point
+                    let point = icon * 2 + if x < ICON_WIDTH / 2 + 1 { 1 } else { 2 };
+                    let color = if point <= value {
+                        full_color
+                    } else {
+                        empty_color
+                    };
+                    self.push_unbounded_rect_uniform_on_frame_buffer(
+                        UnBoundedScreenRect {
+                            x: icon_x + x,
+                            y: pos.y + y as isize,
+                            width: 1,
+                            height: 1,
+                        },
+                        color,
+                    );
+                }
+            }
+        }
+    }
+
     pub fn draw_breaking_indicator(&mut self, tile_x: usize, tile_y: usize, hud: &Hud) {
         if let Some(progress) = hud.breaking_progress {
             let bar_len = (40. * progress) as u16;
@@ -172,22 +268,24 @@ impl Renderer {
         }
     }
 
-    fn draw_scalled_tile_on_frame_buffer(
+    fn draw_scalled_item_on_frame_buffer(
         &mut self,
-        texture_id: u8,
+        texture: ItemTexture,
         pos: Vector2<isize>,
         scale: isize,
     ) {
-        let tileset_x = (texture_id % 16) as usize * 8;
-        let tileset_y = (texture_id / 16) as usize * 8;
+        let (atlas, atlas_x, atlas_y) = get_item_texture_in_atlas(texture);
 
         for x in 0..8 {
             for y in 0..8 {
-                let texture_pixel_index = ((tileset_x + x) + (tileset_y + y) * 128) * 2;
+                let texture_pixel_index = ((atlas_x + x) + (atlas_y + y) * ATLAS_WIDTH) * 2;
                 let pixel = u16::from_be_bytes([
-                    TILESET_DATA[texture_pixel_index],
-                    TILESET_DATA[texture_pixel_index + 1],
+                    atlas[texture_pixel_index],
+                    atlas[texture_pixel_index + 1],
                 ]);
+                if pixel == TRANSPARENT_COLOR {
+                    continue;
+                }
 
                 self.push_unbounded_rect_uniform_on_frame_buffer(
                     UnBoundedScreenRect {
@@ -216,11 +314,9 @@ impl Renderer {
                 GAMEUI_SLOT_COLOR
             },
         );
-        let item_stack = hud.get_slots()[slot_index];
-        let texture_id = item_stack.get_item_type().get_texture_id();
-
-        if texture_id != 0 {
-            self.draw_scalled_tile_on_frame_buffer(texture_id, pos + Vector2::new(3, 3), 3);
+        let item_stack = &hud.get_slots()[slot_index];
+        if let Some(texture) = item_stack.get_item_type().get_texture() {
+            self.draw_scalled_item_on_frame_buffer(texture, pos + Vector2::new(3, 3), 3);
         }
 
         let item_type = item_stack.get_item_type();
@@ -246,6 +342,33 @@ impl Renderer {
                 },
                 Color565::from_rgb888(200, 200, 200),
             );
+        } else if let Some(durability) = item_stack.get_durability()
+            && durability < 1.
+        {
+            // Tools show their remaining uses, from green to red
+            let durability_bar_lenght = (24. * durability) as isize;
+            self.push_unbounded_rect_uniform_on_frame_buffer(
+                UnBoundedScreenRect {
+                    x: pos.x + 3,
+                    y: pos.y + 24,
+                    width: durability_bar_lenght,
+                    height: 3,
+                },
+                Color565::from_rgb888(
+                    (255. * (1. - durability)) as u16,
+                    (255. * durability) as u16,
+                    0,
+                ),
+            );
+            self.push_unbounded_rect_uniform_on_frame_buffer(
+                UnBoundedScreenRect {
+                    x: pos.x + 3 + durability_bar_lenght,
+                    y: pos.y + 24,
+                    width: 24 - durability_bar_lenght,
+                    height: 3,
+                },
+                Color565::from_rgb888(40, 40, 40),
+            );
         }
     }
 }
@@ -4,7 +4,12 @@ use nalgebra::{Vector2, Vector3};
 
 use crate::{
     constants::{world::CHUNK_SIZE, BlockType},
-    world::{chunk::Chunk, chunk_manager::ChunksManager},
+    world::{
+        block_shape::{get_block_boxes, BLOCK_BOXES},
+        block_state::BlockState,
+        chunk::Chunk,
+        chunk_manager::ChunksManager,
+    },
 };
 
 const CHUNK_SIZE_I: isize = CHUNK_SIZE as isize;
@@ -21,6 +26,15 @@ pub enum QuadDir {
 }
 
 impl QuadDir {
+    pub const ALL: [QuadDir; 6] = [
+        QuadDir::Front,
+        QuadDir::Back,
+        QuadDir::Top,
+        QuadDir::Bottom,
+        QuadDir::Right,
+        QuadDir::Left,
+    ];
+
     pub const fn from_id(id: u8) -> Self {
         match id {
             1 => QuadDir::Front,
@@ -43,23 +57,57 @@ impl QuadDir {
             QuadDir::Right => Vector3::new(-1, 0, 0),
         }
     }
+
+    /// Return the opposite direction
+    pub const fn get_opposite(&self) -> Self {
+        match *self {
+            QuadDir::Front => QuadDir::Back,
+            QuadDir::Back => QuadDir::Front,
+            QuadDir::Top => QuadDir::Bottom,
+            QuadDir::Bottom => QuadDir::Top,
+            QuadDir::Left => QuadDir::Right,
+            QuadDir::Right => QuadDir::Left,
+        }
+    }
+
+    /// Return the horizontal direction the closest to the given vector
+    pub fn get_horizontal_from_vector(vector: Vector3<f32>) -> Self {
+        if vector.x.abs() > vector.z.abs() {
+            if vector.x > 0. {
+                QuadDir::Left
+            } else {
+                QuadDir::Right
+            }
+        } else if vector.z > 0. {
+            QuadDir::Back
+        } else {
+            QuadDir::Front
+        }
+    }
 }
 
 pub struct Quad {
     data: u16,
     texture_id: u8,
+    box_id: u8,
 }
 
 impl Quad {
-    pub fn new(pos: Vector3<u16>, dir: QuadDir, texture_id: u8, light: u16) -> Self {
+    /// box_id is the index in BLOCK_BOXES of the box this quad is a face of
+    pub fn new(pos: Vector3<u16>, dir: QuadDir, texture_id: u8, light: u16, box_id: u8) -> Self {
         // xxx yyy zzz ddd llll
         // tttttttt
+        // bbbbbbbb
         let x = pos.x;
         let y = pos.y;
         let z = pos.z;
         let dir = dir as u16;
         let data = x << 13 | y << 10 | z << 7 | dir << 4 | light;
-        Quad { data, texture_id }
+        Quad {
+            data,
+            texture_id,
+            box_id,
+        }
     }
 
     pub fn get_pos(&self) -> nalgebra::Vector3<u16> {
@@ -76,18 +124,31 @@ impl Quad {
         let dir = (self.data & 0b0000000001110000) >> 4;
         QuadDir::from_id(dir as u8)
     }
+
+    /// Return the center of the box of the quad relative to the chunk. Used for sorting.
+    pub fn get_center(&self) -> Vector3<f32> {
+        self.get_pos().map(|x| x as f32) + BLOCK_BOXES[self.box_id as usize].get_center()
+    }
 }
 
 impl Quad {
     pub fn get_triangles(&self, chunk_block_pos: Vector3<isize>) -> (Triangle, Triangle) {
-        let pos = self.get_pos().map(|x| x as isize) + chunk_block_pos;
+        self.get_triangles_at(chunk_block_pos.map(|x| x as f32))
+    }
+
+    /// Same as get_triangles but the quad can be anywhere in the world. Used for the blocks that are not in a chunk.
+    pub fn get_triangles_at(&self, offset: Vector3<f32>) -> (Triangle, Triangle) {
+        let pos = self.get_pos().map(|x| x as f32) + offset;
+        let block_box = &BLOCK_BOXES[self.box_id as usize];
+        let min = pos + block_box.get_min();
+        let max = pos + block_box.get_max();
 
-        let pos_x = (pos.x) as f32;
-        let pos_x_plus_one = pos_x + 1.0;
-        let pos_y = (pos.y) as f32;
-        let pos_y_plus_one = pos_y + 1.0;
-        let pos_z = (pos.z) as f32;
-        let pos_z_plus_one = pos_z + 1.0;
+        let pos_x = min.x;
+        let pos_x_plus_one = max.x;
+        let pos_y = min.y;
+        let pos_y_plus_one = max.y;
+        let pos_z = min.z;
+        let pos_z_plus_one = max.z;
 
         let light = self.get_light_level() as u8;
         match self.get_dir() {
@@ -316,6 +377,25 @@ impl Mesh {
         &mut self.quads
     }
 
+    /// Generate the mesh of a single block at the origin with all its faces visible
+    pub fn generate_block(block_type: BlockType, block_state: BlockState) -> Self {
+        let mut quads = Vec::new();
+
+        for box_id in get_block_boxes(block_type, block_state, |_| None) {
+            for dir in QuadDir::ALL {
+                quads.push(Quad::new(
+                    Vector3::zeros(),
+                    dir,
+                    block_type.get_texture_id(dir, block_state),
+                    Mesh::get_light_level_from_dir(dir),
+                    box_id,
+                ));
+            }
+        }
+
+        Mesh { quads }
+    }
+
     const fn get_light_level_from_dir(dir: QuadDir) -> u16 {
         // Please not bellow 2 to avoid negative light. What is neagative light ?
         match dir {
@@ -336,79 +416,52 @@ impl Mesh {
                 for z in 0..CHUNK_SIZE as isize {
                     let block_type = chunk.get_at_unchecked(Vector3::new(x, y, z));
                     if block_type != BlockType::Air {
+                        let block_state = chunk.get_state_at_unchecked(Vector3::new(x, y, z));
                         let bloc_pos = Vector3::new(x as u16, y as u16, z as u16);
 
                         let grid_additional_light = if (x + y + z) % 2 == 0 { 2 } else { 0 }; // Make one block/2 darker to increase visibility
 
-                        if get_block_in_chunk_or_world(Vector3::new(x, y, z - 1), chunks_manager, chunk)
-                            .is_some_and(|block| block.is_air())
-                        {
-                            quads.push(Quad::new(
-                                bloc_pos,
-                                QuadDir::Front,
-                                block_type.get_texture_id(QuadDir::Front),
-                                Mesh::get_light_level_from_dir(QuadDir::Front)
-                                    - grid_additional_light,
-                            ));
-                        }
-
-                        if get_block_in_chunk_or_world(Vector3::new(x, y, z + 1), chunks_manager, chunk)
-                            .is_some_and(|block| block.is_air())
-                        {
-                            quads.push(Quad::new(
-                                bloc_pos,
-                                QuadDir::Back,
-                                block_type.get_texture_id(QuadDir::Back),
-                                Mesh::get_light_level_from_dir(QuadDir::Back)
-                                    - grid_additional_light,
-                            ));
-                        }
-
-                        if get_block_in_chunk_or_world(Vector3::new(x - 1, y, z), chunks_manager, chunk)
-                            .is_some_and(|block| block.is_air())
-                        {
-                            quads.push(Quad::new(
-                                bloc_pos,
-                                QuadDir::Right,
-                                block_type.get_texture_id(QuadDir::Right),
-                                Mesh::get_light_level_from_dir(QuadDir::Right)
-                                    - grid_additional_light,
-                            ));
-                        }
-                        if get_block_in_chunk_or_world(Vector3::new(x + 1, y, z), chunks_manager, chunk)
-                            .is_some_and(|block| block.is_air())
-                        {
-                            quads.push(Quad::new(
-                                bloc_pos,
-                                QuadDir::Left,
-                                block_type.get_texture_id(QuadDir::Left),
-                                Mesh::get_light_level_from_dir(QuadDir::Left)
-                                    - grid_additional_light,
-                            ));
-                        }
-
-                        if get_block_in_chunk_or_world(Vector3::new(x, y + 1, z), chunks_manager, chunk)
-                            .is_some_and(|block| block.is_air())
-                        {
-                            quads.push(Quad::new(
-                                bloc_pos,
-                                QuadDir::Top,
-                                block_type.get_texture_id(QuadDir::Top),
-                                Mesh::get_light_level_from_dir(QuadDir::Top)
-                                    - grid_additional_light,
-                            ));
-                        }
-
-                        if get_block_in_chunk_or_world(Vector3::new(x, y - 1, z), chunks_manager, chunk)
-                            .is_some_and(|block| block.is_air())
-                        {
-                            quads.push(Quad::new(
-                                bloc_pos,
-                                QuadDir::Bottom,
-                                block_type.get_texture_id(QuadDir::Bottom),
-                                Mesh::get_light_level_from_dir(QuadDir::Bottom)
-                                    - grid_additional_light,
-                            ));
+                        let boxes = get_block_boxes(block_type, block_state, |dir| {
+                            get_block_in_chunk_or_world(
+                                Vector3::new(x, y, z) + dir.get_normal_vector(),
+                                chunks_manager,
+                                chunk,
+                            )
+                        });
+
+                        for box_id in boxes.iter() {
+                            let block_box = &BLOCK_BOXES[*box_id as usize];
+
+                            for dir in QuadDir::ALL {
+                                let visible = if block_box.is_face_on_block_side(dir) {
+                                    // Hide the faces that are against a full block
+                                    get_block_in_chunk_or_world(
+                                        Vector3::new(x, y, z) + dir.get_normal_vector(),
+                                        chunks_manager,
+                                        chunk,
+                                    )
+                                    .is_some_and(|block| !block.is_full_cube())
+                                } else {
+                                    // Hide the faces that are inside of the block
+                                    !boxes.iter().any(|other| {
+                                        other != box_id
+                                            && block_box.is_face_covered_by(
+                                                dir,
+                                                &BLOCK_BOXES[*other as usize],
+                                            )
+                                    })
+                                };
+
+                                if visible {
+                                    quads.push(Quad::new(
+                                        bloc_pos,
+                                        dir,
+                                        block_type.get_texture_id(dir, block_state),
+                                        Mesh::get_light_level_from_dir(dir) - grid_additional_light,
+                                        *box_id,
+                                    ));
+                                }
+                            }
                         }
                     }
                 }
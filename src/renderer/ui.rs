@@ -1,7 +1,10 @@
 use crate::{
     constants::{
         ItemType,
-        color_palette::{GAMEUI_SLOT_COLOR, GAMEUI_SLOT_DEFAULT_OUTLINE_COLOR},
+        color_palette::{
+            GAMEUI_SLOT_COLOR, GAMEUI_SLOT_DEFAULT_OUTLINE_COLOR,
+            GAMEUI_SLOT_HIGHLIGHTED_OUTLINE_COLOR,
+        },
     },
     nadk::{
         self,
@@ -68,20 +71,28 @@ impl Renderer {
         }
     }
 
-    fn draw_scalled_tile_on_screen(&mut self, texture_id: u8, pos: Vector2<u16>, scale: usize) {
-        let tileset_x = (texture_id % 16) as usize * 8;
-        let tileset_y = (texture_id / 16) as usize * 8;
+    fn draw_scalled_item_on_screen(
+        &mut self,
+        texture: ItemTexture,
+        pos: Vector2<u16>,
+        scale: usize,
+    ) {
+        let (atlas, atlas_x, atlas_y) = get_item_texture_in_atlas(texture);
         //let size = (8*scale).pow(2);
 
         //let pixels: Vec<Color> = Vec::with_capacity(size);
 
         for x in 0..8 {
             for y in 0..8 {
-                let texture_pixel_index = ((tileset_x + x) + (tileset_y + y) * 128) * 2;
-                let pixel = u16::from_be_bytes([
-                    TILESET_DATA[texture_pixel_index],
-                    TILESET_DATA[texture_pixel_index + 1],
+                let texture_pixel_index = ((atlas_x + x) + (atlas_y + y) * ATLAS_WIDTH) * 2;
+                let mut pixel = u16::from_be_bytes([
+                    atlas[texture_pixel_index],
+                    atlas[texture_pixel_index + 1],
                 ]);
+                // The screen isn't cleared behind the slots
+                if pixel == TRANSPARENT_COLOR {
+                    pixel = GAMEUI_SLOT_COLOR.value;
+                }
 
                 push_rect_uniform(
                     ScreenRect {
@@ -96,6 +107,46 @@ impl Renderer {
         }
     }
 
+    /// Draw the 30 pixels wide square around a slot
+    fn draw_slot_outline(x: u16, y: u16, color: Color565) {
+        push_rect_uniform(
+            ScreenRect {
+                x: x,
+                y: y,
+                width: 3,
+                height: 30,
+            },
+            color,
+        );
+        push_rect_uniform(
+            ScreenRect {
+                x: x,
+                y: y,
+                width: 30,
+                height: 3,
+            },
+            color,
+        );
+        push_rect_uniform(
+            ScreenRect {
+                x: x,
+                y: y + 27,
+                width: 30,
+                height: 3,
+            },
+            color,
+        );
+        push_rect_uniform(
+            ScreenRect {
+                x: x + 27,
+                y: y,
+                width: 3,
+                height: 30,
+            },
+            color,
+        );
+    }
+
     pub fn draw_game_ui_container(&mut self, game_ui: &mut GameUI, element_id: usize) {
         let element = &game_ui.get_elements()[element_id];
 
@@ -128,49 +179,11 @@ impl Renderer {
                 } else {
                     GAMEUI_SLOT_DEFAULT_OUTLINE_COLOR
                 };
-                // Outline
-                push_rect_uniform(
-                    ScreenRect {
-                        x: x,
-                        y: y,
-                        width: 3,
-                        height: 30,
-                    },
-                    color,
-                );
-                push_rect_uniform(
-                    ScreenRect {
-                        x: x,
-                        y: y,
-                        width: 30,
-                        height: 3,
-                    },
-                    color,
-                );
-                push_rect_uniform(
-                    ScreenRect {
-                        x: x,
-                        y: y + 27,
-                        width: 30,
-                        height: 3,
-                    },
-                    color,
-                );
-                push_rect_uniform(
-                    ScreenRect {
-                        x: x + 27,
-                        y: y,
-                        width: 3,
-                        height: 30,
-                    },
-                    color,
-                );
+                Self::draw_slot_outline(x, y, color);
 
                 // Item texture
-                let texture_id = item_stack.get_item_type().get_texture_id();
-
-                if texture_id != 0 {
-                    self.draw_scalled_tile_on_screen(texture_id, Vector2::new(3 + x, 3 + y), 3);
+                if let Some(texture) = item_stack.get_item_type().get_texture() {
+                    self.draw_scalled_item_on_screen(texture, Vector2::new(3 + x, 3 + y), 3);
 
                     if !item_stack.creative_slot
                         || (game_ui.selected_amount.is_some()
@@ -197,6 +210,35 @@ impl Renderer {
                             GAMEUI_SLOT_COLOR,
                         );
                     }
+
+                    // Remaining uses of the tool
+                    if let Some(durability) = item_stack.get_durability()
+                        && durability < 1.
+                    {
+                        let durability_bar_lenght = (24. * durability) as u16;
+                        push_rect_uniform(
+                            ScreenRect {
+                                x: x + 3,
+                                y: y + 24,
+                                width: durability_bar_lenght,
+                                height: 3,
+                            },
+                            Color565::from_rgb888(
+                                (255. * (1. - durability)) as u16,
+                                (255. * durability) as u16,
+                                0,
+                            ),
+                        );
+                        push_rect_uniform(
+                            ScreenRect {
+                                x: x + 3 + durability_bar_lenght,
+                                y: y + 24,
+                                width: 24 - durability_bar_lenght,
+                                height: 3,
+                            },
+                            Color565::from_rgb888(40, 40, 40),
+                        );
+                    }
                 }
 
                 // Amount selection bar
@@ -241,15 +283,27 @@ impl Renderer {
                 );
             }
             GameUIElements::Arrow { filling } => {
-                nadk::display::push_rect_uniform(
-                    ScreenRect {
-                        x: element.pos.x + 2,
-                        y: element.pos.y + 12,
-                        width: 16,
-                        height: 6,
-                    },
-                    Color565::from_rgb888(150, 150, 150),
-                );
+                // The arrow is filled from left to right, column by column
+                let filled_columns = (filling.clamp(0., 1.) * 27.) as u16;
+                let get_column_color = |column: u16| {
+                    if column < filled_columns {
+                        Color565::from_rgb888(255, 255, 255)
+                    } else {
+                        Color565::from_rgb888(150, 150, 150)
+                    }
+                };
+
+                for i in 0..16 {
+                    nadk::display::push_rect_uniform(
+                        ScreenRect {
+                            x: element.pos.x + 2 + i,
+                            y: element.pos.y + 12,
+                            width: 1,
+                            height: 6,
+                        },
+                        get_column_color(i),
+                    );
+                }
 
                 for i in 0..=10 {
                     nadk::display::push_rect_uniform(
@@ -259,10 +313,40 @@ impl Renderer {
                             width: 1,
                             height: (10 - i) * 2 + 2,
                         },
-                        Color565::from_rgb888(150, 150, 150),
+                        get_column_color(16 + i),
                     );
                 }
             }
+            GameUIElements::Flame { filling } => {
+                // The flame is filled from the bottom, line by line
+                const FLAME_SHAPE: [&str; 8] = [
+                    "...#....", "...##...", "..###...", "..####..", ".######.", ".##.###.",
+                    ".#...##.", "..####..",
+                ];
+                let filled_lines = (filling.clamp(0., 1.) * 8.) as usize;
+
+                for (line_index, line) in FLAME_SHAPE.iter().enumerate() {
+                    let color = if 8 - line_index <= filled_lines {
+                        Color565::from_rgb888(255, 140, 0)
+                    } else {
+                        Color565::from_rgb888(150, 150, 150)
+                    };
+
+                    for (column_index, pixel) in line.bytes().enumerate() {
+                        if pixel == b'#' {
+                            nadk::display::push_rect_uniform(
+                                ScreenRect {
+                                    x: element.pos.x + 7 + column_index as u16 * 2,
+                                    y: element.pos.y + 7 + line_index as u16 * 2,
+                                    width: 2,
+                                    height: 2,
+                                },
+                                color,
+                            );
+                        }
+                    }
+                }
+            }
             GameUIElements::OneWayItemSlot { item_stack, .. } => {
                 // Background
                 if item_stack.get_item_type() == ItemType::Air {
@@ -284,49 +368,11 @@ impl Renderer {
                 } else {
                     GAMEUI_SLOT_DEFAULT_OUTLINE_COLOR
                 };
-                // Outline
-                push_rect_uniform(
-                    ScreenRect {
-                        x: x,
-                        y: y,
-                        width: 3,
-                        height: 30,
-                    },
-                    color,
-                );
-                push_rect_uniform(
-                    ScreenRect {
-                        x: x,
-                        y: y,
-                        width: 30,
-                        height: 3,
-                    },
-                    color,
-                );
-                push_rect_uniform(
-                    ScreenRect {
-                        x: x,
-                        y: y + 27,
-                        width: 30,
-                        height: 3,
-                    },
-                    color,
-                );
-                push_rect_uniform(
-                    ScreenRect {
-                        x: x + 27,
-                        y: y,
-                        width: 3,
-                        height: 30,
-                    },
-                    color,
-                );
+                Self::draw_slot_outline(x, y, color);
 
                 // Item texture
-                let texture_id = item_stack.get_item_type().get_texture_id();
-
-                if texture_id != 0 {
-                    self.draw_scalled_tile_on_screen(texture_id, Vector2::new(3 + x, 3 + y), 3);
+                if let Some(texture) = item_stack.get_item_type().get_texture() {
+                    self.draw_scalled_item_on_screen(texture, Vector2::new(3 + x, 3 + y), 3);
 
                     if !item_stack.creative_slot
                         || (game_ui.selected_amount.is_some()
@@ -335,6 +381,50 @@ impl Renderer {
                         // Item amount
                         let amount_text = format!("{}", item_stack.get_amount());
 
+                        draw_string(
+                            amount_text.as_str(),
+                            ScreenPoint {
+                                x: (30 - 7 * amount_text.len() + x as usize) as u16,
+                                y: y,
+                            },
+                            false,
+                            Color565::from_rgb888(255, 255, 255),
+                            GAMEUI_SLOT_COLOR,
+                        );
+                    }
+                }
+            }
+            GameUIElements::DisplaySlot {
+                item_stack,
+                highlighted,
+            } => {
+                // Background
+                push_rect_uniform(
+                    ScreenRect {
+                        x: x + 3,
+                        y: y + 3,
+                        width: 24,
+                        height: 24,
+                    },
+                    GAMEUI_SLOT_COLOR,
+                );
+
+                let color = if game_ui.cursor_id == element.id {
+                    Color565::from_rgb888(255, 0, 0)
+                } else if *highlighted {
+                    GAMEUI_SLOT_HIGHLIGHTED_OUTLINE_COLOR
+                } else {
+                    GAMEUI_SLOT_DEFAULT_OUTLINE_COLOR
+                };
+                Self::draw_slot_outline(x, y, color);
+
+                // Item texture
+                if let Some(texture) = item_stack.get_item_type().get_texture() {
+                    self.draw_scalled_item_on_screen(texture, Vector2::new(3 + x, 3 + y), 3);
+
+                    if item_stack.get_amount() > 1 {
+                        let amount_text = format!("{}", item_stack.get_amount());
+
                         draw_string(
                             amount_text.as_str(),
                             ScreenPoint {
@@ -5,12 +5,21 @@ calc_use!(alloc::vec::Vec);
 use lz4_flex::{compress, compress_prepend_size, decompress, decompress_size_prepended};
 use nalgebra::Vector3;
 use postcard::{from_bytes, to_allocvec};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 
 use crate::{
-    constants::{BlockType, save_manager::WORLD_VERSION, world::CHUNK_SIZE},
+    constants::{
+        BlockType, EntityType,
+        player::{MAX_HEALTH, MAX_HUNGER},
+        save_manager::{
+            ENTITIES_WORLD_VERSION, HEALTH_WORLD_VERSION, HUNGER_WORLD_VERSION,
+            ITEM_METADATA_WORLD_VERSION, TOOLS_WORLD_VERSION, WORLD_VERSION,
+        },
+        world::CHUNK_SIZE,
+    },
+    entity::{Entity, EntityData},
     game::GameMode,
-    inventory::Inventory,
+    inventory::{Inventory, LegacyInventory, LegacyItemStack, LegacyItemStackWithDamage},
     nadk::{
         self,
         display::Color565,
@@ -21,14 +30,74 @@ use crate::{
     },
     player::Player,
     renderer::Renderer,
-    world::{World, chunk::Chunk},
+    world::{
+        RegisteredInventory, World, block_state::BlockState, chunk::Chunk, furnace::FurnaceState,
+    },
 };
 
+const BLOCK_COUNT: usize = CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE;
+
+/// The inventories of the worlds saved before the tools
+type InventoryBeforeTools = LegacyInventory<LegacyItemStack>;
+/// The inventories of the worlds saved before the item metadata
+type InventoryBeforeMetadata = LegacyInventory<LegacyItemStackWithDamage>;
+
 #[derive(Serialize, Deserialize)]
 pub struct PlayerData {
     pub pos: (f32, f32, f32),
     pub rotation: (f32, f32), // Only Pitch and Yaw
     pub inventory: Inventory, // More in the futur
+    pub health: u8,
+    pub hunger: u8,
+}
+
+/// The player data of the worlds saved before the health, with the inventory in their format
+#[derive(Deserialize)]
+struct LegacyPlayerData<I> {
+    pos: (f32, f32, f32),
+    rotation: (f32, f32),
+    inventory: I,
+}
+
+/// The player data of the worlds saved before the hunger
+#[derive(Deserialize)]
+struct LegacyPlayerDataWithHealth {
+    pos: (f32, f32, f32),
+    rotation: (f32, f32),
+    inventory: Inventory,
+    health: u8,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RegisteredInventoryData {
+    pub inventory: Inventory,
+    pub block_pos: Option<(isize, isize, isize)>,
+}
+
+/// The registered inventories of the worlds saved before the item metadata, with the inventory in their format
+#[derive(Deserialize)]
+struct LegacyRegisteredInventoryData<I> {
+    inventory: I,
+    block_pos: Option<(isize, isize, isize)>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct FurnaceData {
+    pub pos: (isize, isize, isize),
+    pub burn_time: f32,
+    pub max_burn_time: f32,
+    pub cook_time: f32,
+}
+
+/// An entity in the save file. The data is borrowed from the entity when saving and owned when loading.
+#[derive(Serialize, Deserialize)]
+pub struct SavedEntity<D> {
+    pub entity_type: EntityType,
+    pub pos: (f32, f32, f32),
+    pub rotation: (f32, f32), // Only Pitch and Yaw
+    pub velocity: (f32, f32, f32),
+    pub health: Option<u8>,
+    pub data: D,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -37,32 +106,147 @@ pub struct WorldInfo {
     pub world_name: String,
     pub world_seed: i32,
     pub gamemode: GameMode,
+    /// Where the player appears when the world is created and after dying
+    pub spawn_pos: (f32, f32, f32),
+}
+
+/// The world info of the worlds saved before the spawn position
+#[derive(Deserialize)]
+struct LegacyWorldInfo {
+    world_version: u16,
+    world_name: String,
+    world_seed: i32,
+    gamemode: GameMode,
 }
 
 impl WorldInfo {
+    /// Read the world info of any world version.
+    /// The spawn position of the old worlds is unknown, it is set to the player position once it is loaded.
+    fn from_raw(raw: &[u8]) -> Option<Self> {
+        // The world version is always the first field
+        let world_version = from_bytes::<u16>(raw).ok()?;
+
+        if world_version >= HEALTH_WORLD_VERSION {
+            return from_bytes::<WorldInfo>(raw).ok();
+        }
+
+        let legacy = from_bytes::<LegacyWorldInfo>(raw).ok()?;
+        Some(WorldInfo {
+            world_version: legacy.world_version,
+            world_name: legacy.world_name,
+            world_seed: legacy.world_seed,
+            gamemode: legacy.gamemode,
+            spawn_pos: (0., 0., 0.),
+        })
+    }
+
     pub fn new() -> Self {
         WorldInfo {
             world_version: WORLD_VERSION,
             world_name: String::new(),
             world_seed: 1,
             gamemode: GameMode::Survival,
+            spawn_pos: (0., 0., 0.),
         }
     }
 }
 
 impl PlayerData {
+    /// Read the player data saved with the given world version. The missing values are full.
+    fn from_raw(raw: &[u8], world_version: u16) -> Option<Self> {
+        if world_version >= HUNGER_WORLD_VERSION {
+            return from_bytes::<PlayerData>(raw).ok();
+        }
+
+        if world_version >= HEALTH_WORLD_VERSION {
+            let legacy = from_bytes::<LegacyPlayerDataWithHealth>(raw).ok()?;
+            return Some(PlayerData {
+                pos: legacy.pos,
+                rotation: legacy.rotation,
+                inventory: legacy.inventory,
+                health: legacy.health,
+                hunger: MAX_HUNGER,
+            });
+        }
+
+        if world_version >= ITEM_METADATA_WORLD_VERSION {
+            return from_bytes::<LegacyPlayerData<Inventory>>(raw)
+                .ok()
+                .map(LegacyPlayerData::into_player_data);
+        }
+
+        if world_version >= TOOLS_WORLD_VERSION {
+            return from_bytes::<LegacyPlayerData<InventoryBeforeMetadata>>(raw)
+                .ok()
+                .map(LegacyPlayerData::into_player_data);
+        }
+
+        from_bytes::<LegacyPlayerData<InventoryBeforeTools>>(raw)
+            .ok()
+            .map(LegacyPlayerData::into_player_data)
+    }
+
     pub fn new() -> Self {
         PlayerData {
             pos: (0., 0., 0.),
             rotation: (0., 0.),
             inventory: Inventory::new(0),
+            health: MAX_HEALTH,
+            hunger: MAX_HUNGER,
+        }
+    }
+}
+
+impl RegisteredInventoryData {
+    /// Read the registered inventories saved with the given world version
+    fn from_raw_list(raw: &[u8], world_version: u16) -> Option<Vec<Self>> {
+        if world_version >= ITEM_METADATA_WORLD_VERSION {
+            return from_bytes::<Vec<RegisteredInventoryData>>(raw).ok();
+        }
+
+        if world_version >= TOOLS_WORLD_VERSION {
+            return LegacyRegisteredInventoryData::<InventoryBeforeMetadata>::from_raw_list(raw);
+        }
+
+        LegacyRegisteredInventoryData::<InventoryBeforeTools>::from_raw_list(raw)
+    }
+}
+
+impl<I: Into<Inventory>> LegacyPlayerData<I> {
+    fn into_player_data(self) -> PlayerData {
+        PlayerData {
+            pos: self.pos,
+            rotation: self.rotation,
+            inventory: self.inventory.into(),
+            health: MAX_HEALTH,
+            hunger: MAX_HUNGER,
         }
     }
 }
 
+impl<I: Into<Inventory> + DeserializeOwned> LegacyRegisteredInventoryData<I> {
+    /// Read the legacy registered inventories and convert them to the current format
+    fn from_raw_list(raw: &[u8]) -> Option<Vec<RegisteredInventoryData>> {
+        let legacy = from_bytes::<Vec<Self>>(raw).ok()?;
+        Some(
+            legacy
+                .into_iter()
+                .map(|legacy| RegisteredInventoryData {
+                    inventory: legacy.inventory.into(),
+                    block_pos: legacy.block_pos,
+                })
+                .collect(),
+        )
+    }
+}
+
 pub struct SaveManager {
     chunks_data: [Vec<u8>; 64],
     player_data: PlayerData,
+    registered_inventories: Vec<RegisteredInventoryData>,
+    furnaces: Vec<FurnaceData>,
+    /// The serialized entities, ready to be written in the save file
+    entities_data: Vec<u8>,
     world_info: WorldInfo,
     pub file_name: Option<String>,
 }
@@ -72,6 +256,9 @@ impl SaveManager {
         SaveManager {
             chunks_data: [const { Vec::new() }; 64],
             player_data: PlayerData::new(),
+            registered_inventories: Vec::new(),
+            furnaces: Vec::new(),
+            entities_data: Vec::new(),
             world_info: WorldInfo::new(),
             file_name: None,
         }
@@ -97,6 +284,15 @@ impl SaveManager {
         self.world_info.gamemode
     }
 
+    pub fn set_spawn_pos(&mut self, pos: Vector3<f32>) {
+        self.world_info.spawn_pos = (pos.x, pos.y, pos.z);
+    }
+
+    pub fn get_spawn_pos(&self) -> Vector3<f32> {
+        let (x, y, z) = self.world_info.spawn_pos;
+        Vector3::new(x, y, z)
+    }
+
     pub fn set_chunk(&mut self, chunk: &Chunk) -> bool {
         let pos = chunk.get_pos();
 
@@ -104,7 +300,16 @@ impl SaveManager {
             return false;
         }
 
-        let compressed = compress(&chunk.get_all_blocks().map(|b| b as u8));
+        // Block ids followed by block states
+        let mut raw_chunk = [0u8; BLOCK_COUNT * 2];
+        for (i, block_type) in chunk.get_all_blocks().iter().enumerate() {
+            raw_chunk[i] = *block_type as u8;
+        }
+        for (i, state) in chunk.get_all_states().iter().enumerate() {
+            raw_chunk[BLOCK_COUNT + i] = state.get_raw();
+        }
+
+        let compressed = compress(&raw_chunk);
 
         let index = (pos.x + pos.y * 4 + pos.z * 16) as usize;
 
@@ -128,6 +333,52 @@ impl SaveManager {
         self.player_data.rotation.1 = player_entity.rotation.y;
 
         self.player_data.inventory = player.inventory.clone();
+
+        self.player_data.health = player_entity.health.unwrap_or(MAX_HEALTH);
+        self.player_data.hunger = player.hunger;
+    }
+
+    /// Save the inventories and the furnaces of the blocks
+    pub fn update_block_entities(&mut self, world: &World) {
+        self.registered_inventories = world
+            .get_registered_inventories()
+            .iter()
+            .map(|registered| RegisteredInventoryData {
+                inventory: registered.inventory.clone(),
+                block_pos: registered.block_pos.map(|pos| (pos.x, pos.y, pos.z)),
+            })
+            .collect();
+
+        self.furnaces = world
+            .get_furnaces()
+            .iter()
+            .map(|furnace| FurnaceData {
+                pos: (furnace.pos.x, furnace.pos.y, furnace.pos.z),
+                burn_time: furnace.burn_time,
+                max_burn_time: furnace.max_burn_time,
+                cook_time: furnace.cook_time,
+            })
+            .collect();
+    }
+
+    /// Save the entities other than the player
+    pub fn update_entities(&mut self, world: &World) {
+        let entities: Vec<SavedEntity<&EntityData>> = world
+            .get_all_entities()
+            .iter()
+            .skip(1) // The player is saved with its own data
+            .filter(|entity| !entity.removed)
+            .map(|entity| SavedEntity {
+                entity_type: entity.get_type(),
+                pos: (entity.pos.x, entity.pos.y, entity.pos.z),
+                rotation: (entity.rotation.x, entity.rotation.y),
+                velocity: (entity.velocity.x, entity.velocity.y, entity.velocity.z),
+                health: entity.health,
+                data: &entity.data,
+            })
+            .collect();
+
+        self.entities_data = to_allocvec(&entities).unwrap();
     }
 
     pub fn get_existing_worlds(&self) -> Vec<String> {
@@ -186,6 +437,17 @@ impl SaveManager {
         data_to_compress.extend((raw_player_data.len() as u16).to_be_bytes());
         data_to_compress.extend(raw_player_data);
 
+        let raw_registered_inventories = to_allocvec(&self.registered_inventories).unwrap();
+        data_to_compress.extend((raw_registered_inventories.len() as u16).to_be_bytes());
+        data_to_compress.extend(raw_registered_inventories);
+
+        let raw_furnaces = to_allocvec(&self.furnaces).unwrap();
+        data_to_compress.extend((raw_furnaces.len() as u16).to_be_bytes());
+        data_to_compress.extend(raw_furnaces);
+
+        data_to_compress.extend((self.entities_data.len() as u16).to_be_bytes());
+        data_to_compress.extend(&self.entities_data);
+
         data.extend_from_slice(&compress_prepend_size(&data_to_compress));
 
         data
@@ -212,7 +474,7 @@ impl SaveManager {
         // Read the raw data
         let world_info_raw = &data[world_data_offset..(world_data_offset + world_info_size)];
 
-        if let Ok(world_info) = from_bytes::<WorldInfo>(world_info_raw) {
+        if let Some(world_info) = WorldInfo::from_raw(world_info_raw) {
             self.world_info = world_info;
             Ok(world_data_offset + world_info_size)
         } else {
@@ -225,11 +487,7 @@ impl SaveManager {
         let world_info_size = u16::from_be_bytes([raw_data[0], raw_data[1]]);
         let raw_data = &file_read_slice(filename, 2, world_info_size as usize)?;
 
-        if let Ok(world_info) = from_bytes::<WorldInfo>(&raw_data) {
-            Some(world_info)
-        } else {
-            None
-        }
+        WorldInfo::from_raw(raw_data)
     }
 
     pub fn load_from_file(&mut self, filename: &str) -> Result<(), SaveFileLoadError> {
@@ -273,12 +531,99 @@ impl SaveManager {
                     // Read the raw data
                     let player_data_raw = &data[current_pos..(current_pos + player_data_size)];
 
-                    if let Ok(player_data) = from_bytes::<PlayerData>(player_data_raw) {
+                    if let Some(player_data) =
+                        PlayerData::from_raw(player_data_raw, self.world_info.world_version)
+                    {
                         self.player_data = player_data;
                     } else {
                         return Err(SaveFileLoadError::CorruptedWorld);
                     }
 
+                    // Worlds saved before the spawn position respawn the player where it was
+                    if self.world_info.world_version < HEALTH_WORLD_VERSION {
+                        self.world_info.spawn_pos = self.player_data.pos;
+                    }
+
+                    current_pos += player_data_size;
+
+                    // Worlds saved before the chests have no registered inventories
+                    if current_pos + 1 < data.len() {
+                        let inventories_size =
+                            u16::from_be_bytes([data[current_pos], data[current_pos + 1]]) as usize;
+
+                        current_pos += 2; // registered inventories size
+
+                        // Check for overflow
+                        if current_pos + inventories_size > data.len() {
+                            return Err(SaveFileLoadError::CorruptedWorld);
+                        }
+
+                        let inventories_raw = &data[current_pos..(current_pos + inventories_size)];
+
+                        if let Some(registered_inventories) = RegisteredInventoryData::from_raw_list(
+                            inventories_raw,
+                            self.world_info.world_version,
+                        ) {
+                            self.registered_inventories = registered_inventories;
+                        } else {
+                            return Err(SaveFileLoadError::CorruptedWorld);
+                        }
+
+                        current_pos += inventories_size;
+                    }
+
+                    // Worlds saved before the furnaces have no furnace timers
+                    if current_pos + 1 < data.len() {
+                        let furnaces_size =
+                            u16::from_be_bytes([data[current_pos], data[current_pos + 1]]) as usize;
+
+                        current_pos += 2; // furnaces size
+
+                        // Check for overflow
+                        if current_pos + furnaces_size > data.len() {
+                            return Err(SaveFileLoadError::CorruptedWorld);
+                        }
+
+                        let furnaces_raw = &data[current_pos..(current_pos + furnaces_size)];
+
+                        if let Ok(furnaces) = from_bytes::<Vec<FurnaceData>>(furnaces_raw) {
+                            self.furnaces = furnaces;
+                        } else {
+                            return Err(SaveFileLoadError::CorruptedWorld);
+                        }
+
+                        current_pos += furnaces_size;
+                    }
+
+                    // Worlds saved before the entities have no entities
+                    if self.world_info.world_version >= ENTITIES_WORLD_VERSION {
+                        if current_pos + 1 >= data.len() {
+                            return Err(SaveFileLoadError::CorruptedWorld);
+                        }
+
+                        let entities_size =
+                            u16::from_be_bytes([data[current_pos], data[current_pos + 1]]) as usize;
+
+                        current_pos += 2; // entities size
+
+                        // Check for overflow
+                        if current_pos + entities_size > data.len() {
+                            return Err(SaveFileLoadError::CorruptedWorld);
+                        }
+
+                        let entities_raw = &data[current_pos..(current_pos + entities_size)];
+
+                        // The entities are read again when they are spawned
+                        if from_bytes::<Vec<SavedEntity<EntityData>>>(entities_raw).is_ok() {
+                            self.entities_data = entities_raw.to_vec();
+                        } else {
+                            return Err(SaveFileLoadError::CorruptedWorld);
+                        }
+                    }
+
+                    // The world is saved back with the current format
+                    self.world_info.world_version = WORLD_VERSION;
+
                     Ok(())
                 } else {
                     Err(SaveFileLoadError::CorruptedWorld)
@@ -300,20 +645,27 @@ impl SaveManager {
 
         let raw_chunk = &self.chunks_data[index];
 
-        if let Ok(chunk_data) = decompress(raw_chunk, 512) {
-            if chunk_data.len() != 512 {
-                return Err(ChunkReadingError::CorruptedChunk);
-            }
+        if let Ok(chunk_data) = decompress(raw_chunk, BLOCK_COUNT * 2) {
+            // Chunks saved before the block states only contain the block ids
+            let has_states = match chunk_data.len() {
+                BLOCK_COUNT => false,
+                len if len == BLOCK_COUNT * 2 => true,
+                _ => return Err(ChunkReadingError::CorruptedChunk),
+            };
 
             let mut chunk = Chunk::new(pos);
 
             for x in 0..CHUNK_SIZE {
                 for y in 0..CHUNK_SIZE {
                     for z in 0..CHUNK_SIZE {
-                        if let Some(block_type) = BlockType::get_from_id(
-                            chunk_data[x + y * CHUNK_SIZE + z * CHUNK_SIZE * CHUNK_SIZE],
-                        ) {
-                            chunk.set_at(Vector3::new(x, y, z), block_type);
+                        let index = x + y * CHUNK_SIZE + z * CHUNK_SIZE * CHUNK_SIZE;
+                        if let Some(block_type) = BlockType::get_from_id(chunk_data[index]) {
+                            let state = if has_states {
+                                BlockState::from_raw(chunk_data[BLOCK_COUNT + index])
+                            } else {
+                                BlockState::DEFAULT
+                            };
+                            chunk.set_at_with_state(Vector3::new(x, y, z), block_type, state);
                         } else {
                             return Err(ChunkReadingError::CorruptedChunk);
                         }
@@ -343,12 +695,62 @@ impl SaveManager {
         Vector3::new(self.player_data.rotation.0, self.player_data.rotation.1, 0.)
     }
 
+    pub fn get_player_health(&self) -> u8 {
+        self.player_data.health
+    }
+
+    pub fn get_player_hunger(&self) -> u8 {
+        self.player_data.hunger
+    }
+
+    pub fn get_registered_inventories(&self) -> Vec<RegisteredInventory> {
+        self.registered_inventories
+            .iter()
+            .map(|data| RegisteredInventory {
+                inventory: data.inventory.clone(),
+                block_pos: data.block_pos.map(|(x, y, z)| Vector3::new(x, y, z)),
+            })
+            .collect()
+    }
+
+    pub fn get_furnaces(&self) -> Vec<FurnaceState> {
+        self.furnaces
+            .iter()
+            .map(|data| FurnaceState {
+                pos: Vector3::new(data.pos.0, data.pos.1, data.pos.2),
+                burn_time: data.burn_time,
+                max_burn_time: data.max_burn_time,
+                cook_time: data.cook_time,
+            })
+            .collect()
+    }
+
+    /// Spawn the saved entities in the world with new ids
+    pub fn spawn_entities(&self, world: &mut World) {
+        // Worlds saved before the entities have none
+        let entities =
+            from_bytes::<Vec<SavedEntity<EntityData>>>(&self.entities_data).unwrap_or_default();
+
+        for saved in entities {
+            let mut entity = Entity::new(world.get_new_entity_id(), saved.entity_type, saved.data);
+            entity.rotation = Vector3::new(saved.rotation.0, saved.rotation.1, 0.);
+            entity.velocity = Vector3::new(saved.velocity.0, saved.velocity.1, saved.velocity.2);
+            entity.health = saved.health;
+
+            let (x, y, z) = saved.pos;
+            world.spawn_entity(entity, Vector3::new(x, y, z));
+        }
+    }
+
     pub fn clean(&mut self) {
         for chunk in self.chunks_data.iter_mut() {
             chunk.clear();
         }
 
         self.player_data = PlayerData::new();
+        self.registered_inventories.clear();
+        self.furnaces.clear();
+        self.entities_data.clear();
     }
 }
 
@@ -370,9 +772,87 @@ Save file format. World is 4 x 4 x 4 chunks.
 Header:
     4x4x4 x 2 B array : represent the compressed size of the chunk for each chunk
 
-    4x4x4 x variable size : chunks data.
+    4x4x4 x variable size : chunks data. Each chunk is 8x8x8 block ids followed by 8x8x8 block states.
 
     2 + variable : Player info
 
+    2 + variable : Registered inventories (chests, furnaces, ...)
+
+    2 + variable : Furnaces timers
+
+    2 + variable : Entities other than the player
+
     2 + variable : World Info
 */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{constants::ItemType, entity::primed_tnt::PrimedTntData, inventory::ItemStack};
+
+    #[test]
+    fn load_player_data_before_tools() {
+        // Position (1, 2, 3), rotation (0.5, -1) and 3 stone blocks in the only slot
+        let raw = [
+            0, 0, 0x80, 0x3F, 0, 0, 0, 0x40, 0, 0, 0x40, 0x40, 0, 0, 0, 0x3F, 0, 0, 0x80, 0xBF, 2,
+            1, 3, 0, 0, 0, 0, 0,
+        ];
+        let player_data = PlayerData::from_raw(&raw, 0).unwrap();
+
+        assert_eq!(player_data.pos, (1., 2., 3.));
+        assert_eq!(player_data.rotation, (0.5, -1.));
+        assert_eq!(player_data.health, MAX_HEALTH);
+        assert_eq!(player_data.hunger, MAX_HUNGER);
+
+        let slots = player_data.inventory.get_all_slots();
+        assert_eq!(slots.len(), 2);
+        assert_eq!(slots[0], ItemStack::new(ItemType::StoneBlock, 3, false));
+        assert_eq!(slots[1], ItemStack::void());
+    }
+
+    #[test]
+    fn load_world_info_before_spawn_pos() {
+        // Version 0, named "Test", seed 42, in creative
+        let raw = [0, 4, b'T', b'e', b's', b't', 0x54, 1];
+        let world_info = WorldInfo::from_raw(&raw).unwrap();
+
+        assert_eq!(world_info.world_version, 0);
+        assert_eq!(world_info.world_name, "Test");
+        assert_eq!(world_info.world_seed, 42);
+        assert!(world_info.gamemode == GameMode::Creative);
+        assert_eq!(world_info.spawn_pos, (0., 0., 0.));
+    }
+
+    #[test]
+    fn load_current_player_data() {
+        let mut player_data = PlayerData::new();
+        player_data.pos = (4., 5., 6.);
+        player_data.health = 7;
+        let raw = to_allocvec(&player_data).unwrap();
+
+        let loaded = PlayerData::from_raw(&raw, WORLD_VERSION).unwrap();
+        assert_eq!(loaded.pos, (4., 5., 6.));
+        assert_eq!(loaded.health, 7);
+    }
+    #[test]
+    fn load_saved_entities() {
+        let data = EntityData::PrimedTnt(PrimedTntData { fuse: 2. });
+        let saved = Vec::from([SavedEntity {
+            entity_type: EntityType::PrimedTnt,
+            pos: (1., 2., 3.),
+            rotation: (0., 0.),
+            velocity: (0., 0.5, 0.),
+            health: None,
+            data: &data,
+        }]);
+        let raw = to_allocvec(&saved).unwrap();
+
+        let loaded = from_bytes::<Vec<SavedEntity<EntityData>>>(&raw).unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert!(loaded[0].entity_type == EntityType::PrimedTnt);
+        assert_eq!(loaded[0].pos, (1., 2., 3.));
+        assert!(
+            matches!(loaded[0].data, EntityData::PrimedTnt(PrimedTntData { fuse }) if fuse == 2.)
+        );
+    }
+}
@@ -1,23 +1,36 @@
-use core::any::Any;
-
 use crate::constants::world::{
-    CHUNK_SIZE, ITEM_MAGNET_FORCE, MAX_ITEM_MERGING_DISTANCE, MAX_PLAYER_ITEM_MAGNET_DISTANCE,
+    CHUNK_SIZE, ITEM_MAGNET_FORCE, ITEM_PICKUP_DELAY, MAX_ITEM_MERGING_DISTANCE,
+    MAX_PLAYER_ITEM_MAGNET_DISTANCE, TNT_FUSE_DURATION,
 };
 use crate::constants::{BlockType, EntityType, ItemType};
-use crate::entity::Entity;
-use crate::entity::item::ItemEntityCustomData;
+use crate::entity::falling_block::FallingBlockData;
+use crate::entity::item::ItemEntityData;
+use crate::entity::{Entity, EntityData};
 use crate::inventory::{Inventory, ItemStack};
+use crate::world::block_state::BlockState;
+use crate::world::block_updates::ScheduledTick;
 use crate::world::chunk_manager::ChunksManager;
+use crate::world::furnace::FurnaceState;
 use crate::world::world_generator::WorldGenerator;
 
-calc_use!(alloc::boxed::Box);
 calc_use!(alloc::vec::Vec);
 calc_use!(alloc::vec);
 
 use nalgebra::Vector3;
+use rand_core::SeedableRng;
+use rand_xorshift::XorShiftRng;
 
+pub mod block_shape;
+pub mod block_state;
+mod block_ticks;
+mod block_updates;
 pub mod chunk;
 pub mod chunk_manager;
+mod explosion;
+pub mod furnace;
+mod mobs;
+pub mod pathfinding;
+pub mod redstone;
 mod structures;
 pub mod world_generator;
 
@@ -25,15 +38,24 @@ const CHUNK_SIZE_I: isize = CHUNK_SIZE as isize;
 
 pub struct World {
     pub chunks_manager: ChunksManager,
-    registered_inventories: Vec<Inventory>,
+    registered_inventories: Vec<RegisteredInventory>,
+    furnaces: Vec<FurnaceState>,
     loaded_entities: Vec<Entity>,
     next_available_entity_id: usize,
     world_generator: WorldGenerator,
+    rng: XorShiftRng,
+    random_ticks_accumulator: f32,
+    mob_spawn_accumulator: f32,
+    pending_block_updates: Vec<Vector3<isize>>,
+    scheduled_ticks: Vec<ScheduledTick>,
+    current_block_tick: u32,
+    block_tick_accumulator: f32,
 }
 
+/// An inventory stored in the world, bound to a block like a chest
 pub struct RegisteredInventory {
-    inventory: Inventory,
-    block_pos: Option<Vector3<usize>>,
+    pub inventory: Inventory,
+    pub block_pos: Option<Vector3<isize>>,
 }
 
 impl Default for World {
@@ -48,9 +70,17 @@ impl World {
         let mut world = World {
             chunks_manager: ChunksManager::new(),
             registered_inventories: Vec::new(),
-            loaded_entities: vec![Entity::new(0, EntityType::Player, None)], // The player entity is always loaded and id 0
+            furnaces: Vec::new(),
+            loaded_entities: vec![Entity::new(0, EntityType::Player, EntityData::None)], // The player entity is always loaded and id 0
             next_available_entity_id: 1,
             world_generator: WorldGenerator::new(),
+            rng: XorShiftRng::seed_from_u64(0),
+            random_ticks_accumulator: 0.,
+            mob_spawn_accumulator: 0.,
+            pending_block_updates: Vec::new(),
+            scheduled_ticks: Vec::new(),
+            current_block_tick: 0,
+            block_tick_accumulator: 0.,
         };
 
         world
@@ -89,27 +119,48 @@ impl World {
                 }
             }
         }
+
+        self.spawn_generation_mobs(x_start, x_stop, z_start, z_stop);
     }
 
     pub fn update_entities(&mut self, delta_time: f32) {
+        self.update_primed_tnt(delta_time);
+        self.update_mobs(delta_time);
+
+        // Count down the pickup delay of the thrown items
+        for entity in self.loaded_entities.iter_mut() {
+            if entity.get_type() == EntityType::Item
+                && let Some(item_data) = ItemEntityData::get_item_data_mut(entity)
+                && item_data.pickup_delay > 0.
+            {
+                item_data.pickup_delay -= delta_time;
+            }
+        }
+
+        // Count down the time the hurt entities are drawn in red
+        for entity in self.loaded_entities.iter_mut() {
+            entity.hurt_timer = (entity.hurt_timer - delta_time).max(0.);
+        }
+
         // Check for item merging and player magnet
         'first_loop: for i in 0..self.loaded_entities.len() {
             if self.loaded_entities[i].get_type() == EntityType::Item {
-                // Ignore items with custom data = None, they will be removed after
-                if self.loaded_entities[i].custom_data.is_none() {
+                // Ignore the removed items, they will be removed after
+                if self.loaded_entities[i].removed {
                     continue;
                 }
 
                 // Get the item_data from the first item
-                let first_item_data = ItemEntityCustomData::get_item_data(&self.loaded_entities[i])
-                    .expect("Item Entity must have ItemData as custom data.");
+                let first_item_data = ItemEntityData::get_item_data(&self.loaded_entities[i])
+                    .expect("Item Entity must have ItemEntityData as data.");
                 let first_item_stack = first_item_data.item_stack.clone();
+                let first_pickup_delay = first_item_data.pickup_delay;
 
                 let max_stack = first_item_stack.get_item_type().get_max_stack_amount();
 
                 for j in 0..self.loaded_entities.len() {
                     if i != j
-                        && self.loaded_entities[j].custom_data.is_some() // Ignore items with custom data = None, they will be removed after
+                        && !self.loaded_entities[j].removed
                         && self.loaded_entities[j].get_type() == EntityType::Item
                         && self.loaded_entities[i]
                             .pos
@@ -118,11 +169,11 @@ impl World {
                     {
                         // Check if the items can merge
                         let second_item_data =
-                            ItemEntityCustomData::get_item_data(&self.loaded_entities[j])
-                                .expect("Item Entity must have ItemData as custom data.");
+                            ItemEntityData::get_item_data(&self.loaded_entities[j])
+                                .expect("Item Entity must have ItemEntityData as data.");
                         let second_item_stack = second_item_data.item_stack.clone();
 
-                        if second_item_stack.get_item_type() != first_item_stack.get_item_type() {
+                        if !second_item_stack.can_stack_with(&first_item_stack) {
                             continue;
                         }
 
@@ -140,34 +191,22 @@ impl World {
                                 first_item_stack.get_amount() + second_item_stack.get_amount();
                             if total <= max_stack {
                                 // Merge the two items together and request the deletion of the second one
-                                self.loaded_entities[i].custom_data =
-                                    Some(Box::new(ItemEntityCustomData {
-                                        item_stack: ItemStack::new(
-                                            first_item_stack.get_item_type(),
-                                            total,
-                                            false,
-                                        ),
-                                    }));
-                                self.loaded_entities[j].custom_data = None; // Yes, this should be illegal but it can also be a feature.
+                                self.loaded_entities[i].data = EntityData::Item(ItemEntityData {
+                                    item_stack: first_item_stack.with_amount(total),
+                                    pickup_delay: first_pickup_delay,
+                                });
+                                self.loaded_entities[j].removed = true;
                                 self.loaded_entities[i].velocity = Vector3::zeros();
                                 continue 'first_loop;
                             } else {
-                                self.loaded_entities[i].custom_data =
-                                    Some(Box::new(ItemEntityCustomData {
-                                        item_stack: ItemStack::new(
-                                            first_item_stack.get_item_type(),
-                                            max_stack,
-                                            false,
-                                        ),
-                                    }));
-                                self.loaded_entities[j].custom_data =
-                                    Some(Box::new(ItemEntityCustomData {
-                                        item_stack: ItemStack::new(
-                                            first_item_stack.get_item_type(),
-                                            total - max_stack,
-                                            false,
-                                        ),
-                                    }));
+                                self.loaded_entities[i].data = EntityData::Item(ItemEntityData {
+                                    item_stack: first_item_stack.with_amount(max_stack),
+                                    pickup_delay: first_pickup_delay,
+                                });
+                                self.loaded_entities[j].data = EntityData::Item(ItemEntityData {
+                                    item_stack: first_item_stack.with_amount(total - max_stack),
+                                    pickup_delay: first_pickup_delay,
+                                });
                                 self.loaded_entities[i].velocity = Vector3::zeros();
                                 self.loaded_entities[j].velocity = Vector3::zeros();
                             }
@@ -199,7 +238,7 @@ impl World {
                 .pos
                 .metric_distance(&self.get_player_entity().pos);
             if self.loaded_entities[i].get_type() == EntityType::Item
-                && self.loaded_entities[i].custom_data.is_some()
+                && ItemEntityData::can_be_picked_up(&self.loaded_entities[i])
                 && distance < MAX_PLAYER_ITEM_MAGNET_DISTANCE
             {
                 let direction =
@@ -209,20 +248,103 @@ impl World {
             }
         }
 
-        // Remove illegal items
-        self.loaded_entities.retain(|entity| {
-            entity.get_type() != EntityType::Item
-                || (entity.get_type() == EntityType::Item && !entity.custom_data.is_none())
-        });
+        // Remove the entities marked as removed during the update
+        self.loaded_entities.retain(|entity| !entity.removed);
+    }
+
+    /// Interact with the block at the given position. Return true if the block reacted to the interaction
+    pub fn interact_with_block(&mut self, pos: Vector3<isize>) -> bool {
+        let Some(block_type) = self.chunks_manager.get_block_in_world(pos) else {
+            return false;
+        };
+        let state = self
+            .chunks_manager
+            .get_block_state_in_world(pos)
+            .unwrap_or(BlockState::DEFAULT);
+
+        match block_type {
+            BlockType::Tnt => {
+                self.prime_tnt(pos, TNT_FUSE_DURATION);
+                true
+            }
+            _ => self.interact_with_redstone_component(pos, block_type, state),
+        }
     }
 
     /// Set the world generation seed
     pub fn set_seed(&mut self, seed: i32) {
         self.world_generator.set_seed(seed);
+        self.rng = XorShiftRng::seed_from_u64(seed as u64);
     }
 
-    fn register_inventory(&mut self, inventory: Inventory) {
-        self.registered_inventories.push(inventory);
+    fn register_inventory(&mut self, inventory: Inventory, block_pos: Option<Vector3<isize>>) {
+        self.registered_inventories.push(RegisteredInventory {
+            inventory,
+            block_pos,
+        });
+    }
+
+    /// Return the inventory bound to the block at the given position
+    pub fn get_block_inventory_mut(&mut self, pos: Vector3<isize>) -> Option<&mut Inventory> {
+        self.registered_inventories
+            .iter_mut()
+            .find(|registered| registered.block_pos == Some(pos))
+            .map(|registered| &mut registered.inventory)
+    }
+
+    /// Unbind the inventory from the block at the given position and return it
+    fn remove_block_inventory(&mut self, pos: Vector3<isize>) -> Option<Inventory> {
+        let index = self
+            .registered_inventories
+            .iter()
+            .position(|registered| registered.block_pos == Some(pos))?;
+        Some(self.registered_inventories.remove(index).inventory)
+    }
+
+    /// Bind a new inventory to the placed blocks that store items and drop the content of the removed ones.
+    /// Also create and remove the furnaces timers.
+    fn update_block_inventory(
+        &mut self,
+        pos: Vector3<isize>,
+        previous_block: Option<BlockType>,
+        block_type: BlockType,
+    ) {
+        if previous_block == Some(block_type) {
+            return;
+        }
+
+        if let Some(inventory) = self.remove_block_inventory(pos) {
+            for item_stack in inventory.get_all_slots() {
+                if item_stack.get_item_type() != ItemType::Air {
+                    self.spawn_item_entity(pos.map(|v| v as f32 + 0.5), item_stack.clone());
+                }
+            }
+        }
+
+        if let Some(size) = block_type.get_inventory_size() {
+            self.register_inventory(Inventory::new(size), Some(pos));
+        }
+
+        self.furnaces.retain(|furnace| furnace.pos != pos);
+        if block_type == BlockType::Furnace {
+            self.furnaces.push(FurnaceState::new(pos));
+        }
+    }
+
+    pub fn get_registered_inventories(&self) -> &Vec<RegisteredInventory> {
+        &self.registered_inventories
+    }
+
+    pub fn set_registered_inventories(&mut self, registered_inventories: Vec<RegisteredInventory>) {
+        self.registered_inventories = registered_inventories;
+    }
+
+    pub fn get_furnaces(&self) -> &Vec<FurnaceState> {
+        &self.furnaces
+    }
+
+    pub fn set_furnaces(&mut self, furnaces: Vec<FurnaceState>) {
+        self.furnaces = furnaces;
     }
 
     pub fn get_all_entities_mut(&mut self) -> &mut Vec<Entity> {
@@ -263,37 +385,122 @@ impl World {
         }
     }
 
+    /// Spawn a new entity with a new id. Return the id of the entity
     pub fn spawn_entity_auto(
         &mut self,
         entity_type: EntityType,
         pos: Vector3<f32>,
-        custom_data: Option<Box<dyn Any>>,
-    ) {
+        data: EntityData,
+    ) -> usize {
         let id = self.get_new_entity_id();
-        self.spawn_entity(Entity::new(id, entity_type, custom_data), pos);
+        self.spawn_entity(Entity::new(id, entity_type, data), pos);
+        id
     }
 
     pub fn spawn_item_entity(&mut self, pos: Vector3<f32>, item_stack: ItemStack) {
         self.spawn_entity_auto(
             EntityType::Item,
             pos,
-            Some(Box::new(ItemEntityCustomData { item_stack })),
+            EntityData::Item(ItemEntityData {
+                item_stack,
+                pickup_delay: 0.,
+            }),
+        );
+    }
+
+    /// Spawn an item thrown by the player. It can't be picked up during ITEM_PICKUP_DELAY.
+    pub fn throw_item_entity(
+        &mut self,
+        pos: Vector3<f32>,
+        velocity: Vector3<f32>,
+        item_stack: ItemStack,
+    ) {
+        let mut entity = Entity::new(
+            self.get_new_entity_id(),
+            EntityType::Item,
+            EntityData::Item(ItemEntityData {
+                item_stack,
+                pickup_delay: ITEM_PICKUP_DELAY,
+            }),
         );
+        entity.velocity = velocity;
+        self.spawn_entity(entity, pos);
     }
 
     pub fn replace_block_and_drop_item(&mut self, pos: Vector3<isize>, block_type: BlockType) {
         if let Some(current_block) = self.chunks_manager.get_block_in_world(pos) {
             let drop_type = current_block.get_dropped_item_type();
+            let drop_amount = current_block.get_dropped_amount(
+                self.chunks_manager
+                    .get_block_state_in_world(pos)
+                    .unwrap_or(BlockState::DEFAULT),
+            );
+            self.set_block_in_world(pos, block_type);
             if drop_type != ItemType::Air {
-                self.chunks_manager.set_block_in_world(pos, block_type);
                 self.spawn_item_entity(
                     pos.map(|v| v as f32 + 0.5),
-                    ItemStack::new(drop_type, 1, false),
+                    ItemStack::new(drop_type, drop_amount, false),
                 );
             }
         }
     }
 
+    /// Set the block at the given position and notify its neighbors
+    pub fn set_block_in_world(&mut self, pos: Vector3<isize>, block_type: BlockType) -> bool {
+        self.set_block_in_world_with_state(pos, block_type, BlockState::DEFAULT)
+    }
+
+    /// Set the block and its state at the given position and notify its neighbors
+    pub fn set_block_in_world_with_state(
+        &mut self,
+        pos: Vector3<isize>,
+        block_type: BlockType,
+        state: BlockState,
+    ) -> bool {
+        let previous_block = self.chunks_manager.get_block_in_world(pos);
+        if self
+            .chunks_manager
+            .set_block_in_world_with_state(pos, block_type, state)
+        {
+            self.update_block_inventory(pos, previous_block, block_type);
+            self.notify_neighbors(pos);
+            if block_type.is_redstone_component()
+                || previous_block.is_some_and(|b| b.is_redstone_component())
+            {
+                self.notify_redstone_neighbors(pos);
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Turn the gravity affected block at the given position into a falling block if nothing supports it
+    fn update_falling_block(&mut self, pos: Vector3<isize>) {
+        if let Some(block_type) = self.chunks_manager.get_block_in_world(pos)
+            && block_type.has_gravity()
+            && self
+                .chunks_manager
+                .get_block_in_world(pos - Vector3::new(0, 1, 0))
+                .is_some_and(|b| b.is_air())
+        {
+            let block_state = self
+                .chunks_manager
+                .get_block_state_in_world(pos)
+                .unwrap_or(BlockState::DEFAULT);
+
+            self.set_block_in_world(pos, BlockType::Air);
+            self.spawn_entity_auto(
+                EntityType::FallingBlock,
+                pos.map(|v| v as f32 + 0.5),
+                EntityData::FallingBlock(FallingBlockData {
+                    block_type,
+                    block_state,
+                }),
+            );
+        }
+    }
+
     pub fn remove_entity(&mut self, id: usize) -> bool {
         for i in 0..self.loaded_entities.len() {
             if self.loaded_entities[i].get_id() == id {
@@ -331,5 +538,12 @@ impl World {
     pub fn clear(&mut self) {
         self.chunks_manager.clear();
         self.clear_entities();
+        self.pending_block_updates.clear();
+        self.scheduled_ticks.clear();
+        self.registered_inventories.clear();
+        self.furnaces.clear();
+        self.random_ticks_accumulator = 0.;
+        self.mob_spawn_accumulator = 0.;
+        self.block_tick_accumulator = 0.;
     }
 }
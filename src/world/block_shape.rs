@@ -0,0 +1,255 @@
+use nalgebra::Vector3;
+
+use crate::{
+    constants::BlockType,
+    physic::BoundingBox,
+    renderer::mesh::QuadDir,
+    world::block_state::{BlockState, TOP_SLAB_VALUE},
+};
+
+/// The maximum number of boxes a block can be made of.
+pub const MAX_BLOCK_BOXES: usize = 9;
+
+/// The geometry of a block. Every block that is not a full cube is made of several boxes.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BlockShape {
+    Cube,
+    Slab,
+    Stairs,
+    Fence,
+    Plant,
+    Wire,
+    Lever,
+    Torch,
+    Button,
+    Repeater,
+}
+
+/// An axis aligned box inside of a block. The coordinates are in 16th of block.
+#[derive(Clone, Copy, Debug)]
+pub struct BlockBox {
+    pub min: Vector3<u8>,
+    pub max: Vector3<u8>,
+}
+
+impl BlockBox {
+    const fn new(min_x: u8, min_y: u8, min_z: u8, max_x: u8, max_y: u8, max_z: u8) -> Self {
+        BlockBox {
+            min: Vector3::new(min_x, min_y, min_z),
+            max: Vector3::new(max_x, max_y, max_z),
+        }
+    }
+
+    /// Return the position of the lowest corner of the box relative to the block
+    pub fn get_min(&self) -> Vector3<f32> {
+        self.min.map(|v| v as f32 / 16.)
+    }
+
+    /// Return the position of the highest corner of the box relative to the block
+    pub fn get_max(&self) -> Vector3<f32> {
+        self.max.map(|v| v as f32 / 16.)
+    }
+
+    /// Return the center of the box relative to the block
+    pub fn get_center(&self) -> Vector3<f32> {
+        (self.get_min() + self.get_max()) / 2.
+    }
+
+    /// Convert the box into a bounding box in world space
+    pub fn to_bounding_box(self, block_pos: Vector3<isize>) -> BoundingBox {
+        BoundingBox {
+            offset: block_pos.map(|v| v as f32) + self.get_min(),
+            size: self.get_max() - self.get_min(),
+        }
+    }
+
+    /// Return true if the given face of the box is on the side of the block
+    pub fn is_face_on_block_side(&self, dir: QuadDir) -> bool {
+        let (axis, positive) = get_face_axis(dir);
+        if positive {
+            self.max[axis] == 16
+        } else {
+            self.min[axis] == 0
+        }
+    }
+
+    /// Return true if the given face of the box is entirely hidden by the other box
+    pub fn is_face_covered_by(&self, dir: QuadDir, other: &BlockBox) -> bool {
+        let (axis, positive) = get_face_axis(dir);
+
+        let touching = if positive {
+            other.min[axis] == self.max[axis]
+        } else {
+            other.max[axis] == self.min[axis]
+        };
+
+        touching
+            && (0..3)
+                .filter(|a| *a != axis)
+                .all(|a| other.min[a] <= self.min[a] && other.max[a] >= self.max[a])
+    }
+
+    /// Cast a ray on the box. Return the distance to the box and the face that has been hit.
+    pub fn ray_intersection(
+        &self,
+        block_pos: Vector3<isize>,
+        origin: &Vector3<f32>,
+        dir: &Vector3<f32>,
+    ) -> Option<(f32, QuadDir)> {
+        let min = block_pos.map(|v| v as f32) + self.get_min();
+        let max = block_pos.map(|v| v as f32) + self.get_max();
+
+        let mut t_enter = f32::NEG_INFINITY;
+        let mut t_exit = f32::INFINITY;
+        let mut enter_axis = 0;
+
+        for axis in 0..3 {
+            if dir[axis] == 0. {
+                if origin[axis] < min[axis] || origin[axis] > max[axis] {
+                    return None;
+                }
+                continue;
+            }
+            let t1 = (min[axis] - origin[axis]) / dir[axis];
+            let t2 = (max[axis] - origin[axis]) / dir[axis];
+            let (near, far) = if t1 < t2 { (t1, t2) } else { (t2, t1) };
+
+            if near > t_enter {
+                t_enter = near;
+                enter_axis = axis;
+            }
+            t_exit = t_exit.min(far);
+        }
+
+        if t_enter > t_exit || t_exit < 0. {
+            return None;
+        }
+
+        // The hit face is the one facing the ray
+        let face = match (enter_axis, dir[enter_axis] < 0.) {
+            (0, true) => QuadDir::Left,
+            (0, false) => QuadDir::Right,
+            (1, true) => QuadDir::Top,
+            (1, false) => QuadDir::Bottom,
+            (_, true) => QuadDir::Back,
+            (_, false) => QuadDir::Front,
+        };
+
+        Some((t_enter.max(0.), face))
+    }
+}
+
+/// Return the axis of the face normal and true if the normal is pointing toward the positive side
+const fn get_face_axis(dir: QuadDir) -> (usize, bool) {
+    match dir {
+        QuadDir::Front => (2, false),
+        QuadDir::Back => (2, true),
+        QuadDir::Top => (1, true),
+        QuadDir::Bottom => (1, false),
+        QuadDir::Left => (0, true),
+        QuadDir::Right => (0, false),
+    }
+}
+
+pub const FULL_BOX: u8 = 0;
+const SLAB_BOX: u8 = 1;
+const TOP_SLAB_BOX: u8 = 2;
+const STAIRS_START_BOX: u8 = 3;
+const FENCE_POST_BOX: u8 = 11;
+const FENCE_ARMS_START_BOX: u8 = 12;
+const PLANT_BOX: u8 = 20;
+const WIRE_BOX: u8 = 21;
+const LEVER_BOX: u8 = 22;
+const TORCH_BOX: u8 = 23;
+const BUTTON_BOX: u8 = 24;
+const REPEATER_BOX: u8 = 25;
+
+/// All the boxes used by the blocks. Quads only store the index of their box in this table.
+pub const BLOCK_BOXES: [BlockBox; 26] = [
+    BlockBox::new(0, 0, 0, 16, 16, 16), // Full block
+    BlockBox::new(0, 0, 0, 16, 8, 16),  // Slab
+    BlockBox::new(0, 8, 0, 16, 16, 16), // Top slab
+    // Stairs. A step and a back for each facing, in the same order as STAIRS_FACINGS
+    BlockBox::new(0, 0, 0, 16, 8, 8),
+    BlockBox::new(0, 0, 8, 16, 16, 16),
+    BlockBox::new(0, 0, 8, 16, 8, 16),
+    BlockBox::new(0, 0, 0, 16, 16, 8),
+    BlockBox::new(0, 0, 0, 8, 8, 16),
+    BlockBox::new(8, 0, 0, 16, 16, 16),
+    BlockBox::new(8, 0, 0, 16, 8, 16),
+    BlockBox::new(0, 0, 0, 8, 16, 16),
+    BlockBox::new(6, 0, 6, 10, 16, 10), // Fence post
+    // Fence arms. Two rails for each direction, in the same order as FENCE_ARMS_DIRS
+    BlockBox::new(7, 6, 0, 9, 9, 6),
+    BlockBox::new(7, 12, 0, 9, 15, 6),
+    BlockBox::new(7, 6, 10, 9, 9, 16),
+    BlockBox::new(7, 12, 10, 9, 15, 16),
+    BlockBox::new(0, 6, 7, 6, 9, 9),
+    BlockBox::new(0, 12, 7, 6, 15, 9),
+    BlockBox::new(10, 6, 7, 16, 9, 9),
+    BlockBox::new(10, 12, 7, 16, 15, 9),
+    BlockBox::new(5, 0, 5, 11, 12, 11), // Plant
+    BlockBox::new(0, 0, 0, 16, 1, 16),  // Redstone wire
+    BlockBox::new(5, 0, 4, 11, 6, 12),  // Lever
+    BlockBox::new(7, 0, 7, 9, 10, 9),   // Redstone torch
+    BlockBox::new(5, 0, 6, 11, 2, 10),  // Button
+    BlockBox::new(0, 0, 0, 16, 2, 16),  // Repeater
+];
+
+const STAIRS_FACINGS: [QuadDir; 4] = [QuadDir::Back, QuadDir::Front, QuadDir::Left, QuadDir::Right];
+const FENCE_ARMS_DIRS: [QuadDir; 4] =
+    [QuadDir::Front, QuadDir::Back, QuadDir::Right, QuadDir::Left];
+
+/// Return the indexes in BLOCK_BOXES of the boxes composing the block.
+/// get_neighbor gives the block next to this one in the given direction.
+pub fn get_block_boxes(
+    block_type: BlockType,
+    block_state: BlockState,
+    get_neighbor: impl Fn(QuadDir) -> Option<BlockType>,
+) -> heapless::Vec<u8, MAX_BLOCK_BOXES> {
+    let mut boxes = heapless::Vec::new();
+
+    // A block has at most MAX_BLOCK_BOXES boxes so the pushes can't fail
+    match block_type.get_shape() {
+        BlockShape::Cube => {
+            if !block_type.is_air() {
+                boxes.push(FULL_BOX).unwrap();
+            }
+        }
+        BlockShape::Slab => {
+            if block_state.get_value() == TOP_SLAB_VALUE {
+                boxes.push(TOP_SLAB_BOX).unwrap();
+            } else {
+                boxes.push(SLAB_BOX).unwrap();
+            }
+        }
+        BlockShape::Stairs => {
+            // The back of the stairs is on the side of the facing
+            let facing_index = STAIRS_FACINGS
+                .iter()
+                .position(|dir| block_state.get_facing() == Some(*dir))
+                .unwrap_or(0) as u8;
+            boxes.push(STAIRS_START_BOX + facing_index * 2).unwrap();
+            boxes.push(STAIRS_START_BOX + facing_index * 2 + 1).unwrap();
+        }
+        BlockShape::Fence => {
+            boxes.push(FENCE_POST_BOX).unwrap();
+            for (i, dir) in FENCE_ARMS_DIRS.iter().enumerate() {
+                if get_neighbor(*dir)
+                    .is_some_and(|b| b.get_shape() == BlockShape::Fence || b.is_full_cube())
+                {
+                    boxes.push(FENCE_ARMS_START_BOX + i as u8 * 2).unwrap();
+                    boxes.push(FENCE_ARMS_START_BOX + i as u8 * 2 + 1).unwrap();
+                }
+            }
+        }
+        BlockShape::Plant => boxes.push(PLANT_BOX).unwrap(),
+        BlockShape::Wire => boxes.push(WIRE_BOX).unwrap(),
+        BlockShape::Lever => boxes.push(LEVER_BOX).unwrap(),
+        BlockShape::Torch => boxes.push(TORCH_BOX).unwrap(),
+        BlockShape::Button => boxes.push(BUTTON_BOX).unwrap(),
+        BlockShape::Repeater => boxes.push(REPEATER_BOX).unwrap(),
+    }
+
+    boxes
+}
@@ -0,0 +1,85 @@
+use nalgebra::Vector3;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::BlockType,
+    renderer::mesh::QuadDir,
+    world::{block_shape::BlockShape, redstone::REDSTONE_MAX_POWER},
+};
+
+/// The compact state stored alongside each block of a chunk.
+/// fff vvvvv
+/// f : facing. The id of a QuadDir or 0 if the block has no orientation.
+/// v : value. Its meaning depends on the block (variant, growth stage, ...)
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct BlockState(u8);
+
+/// Slabs with this value are placed in the upper half of the block
+pub const TOP_SLAB_VALUE: u8 = 1;
+
+/// Leaves with this value never decay. Used for the leaves placed by the player.
+pub const PERSISTENT_LEAVES_VALUE: u8 = 1;
+
+impl BlockState {
+    pub const DEFAULT: BlockState = BlockState(0);
+    pub const MAX_VALUE: u8 = 0b11111;
+
+    pub const fn from_raw(raw: u8) -> Self {
+        BlockState(raw)
+    }
+
+    pub const fn get_raw(&self) -> u8 {
+        self.0
+    }
+
+    pub const fn get_facing(&self) -> Option<QuadDir> {
+        let id = self.0 >> 5;
+        if id >= 1 && id <= 6 {
+            Some(QuadDir::from_id(id))
+        } else {
+            None
+        }
+    }
+
+    pub const fn with_facing(self, facing: QuadDir) -> Self {
+        BlockState((self.0 & Self::MAX_VALUE) | (facing as u8) << 5)
+    }
+
+    pub const fn get_value(&self) -> u8 {
+        self.0 & Self::MAX_VALUE
+    }
+
+    pub const fn with_value(self, value: u8) -> Self {
+        BlockState((self.0 & !Self::MAX_VALUE) | (value & Self::MAX_VALUE))
+    }
+
+    /// Return the state of a block placed by the player on the face `hit_face` of another block.
+    /// `forward` is the direction the player is looking at.
+    pub fn get_placement_state(
+        block_type: BlockType,
+        hit_face: QuadDir,
+        forward: Vector3<f32>,
+    ) -> BlockState {
+        match block_type {
+            // Logs are aligned with the face they are placed on
+            BlockType::Log => BlockState::DEFAULT.with_facing(hit_face),
+            BlockType::Leaves => BlockState::DEFAULT.with_value(PERSISTENT_LEAVES_VALUE),
+            // Torches are lit until something powers the block under them
+            BlockType::RedstoneTorch => BlockState::DEFAULT.with_value(REDSTONE_MAX_POWER),
+            // Repeaters send the power away from the player
+            BlockType::Repeater => {
+                BlockState::DEFAULT.with_facing(QuadDir::get_horizontal_from_vector(forward))
+            }
+            _ => match block_type.get_shape() {
+                // The highest part of the stairs is on the far side from the player
+                BlockShape::Stairs => {
+                    BlockState::DEFAULT.with_facing(QuadDir::get_horizontal_from_vector(forward))
+                }
+                BlockShape::Slab if hit_face == QuadDir::Bottom => {
+                    BlockState::DEFAULT.with_value(TOP_SLAB_VALUE)
+                }
+                _ => BlockState::DEFAULT,
+            },
+        }
+    }
+}
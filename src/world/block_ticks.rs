@@ -0,0 +1,170 @@
+use nalgebra::Vector3;
+use rand_core::RngCore;
+
+use crate::{
+    constants::{
+        BlockType, ItemType,
+        world::{
+            APPLE_DROP_CHANCE, CHUNK_SIZE, CROP_GROWTH_STAGES, LEAVES_DECAY_LOG_DISTANCE,
+            RANDOM_TICKS_PER_CHUNK, SAPLING_DROP_CHANCE, SAPLING_GROWTH_STAGES,
+        },
+    },
+    inventory::ItemStack,
+    world::{
+        CHUNK_SIZE_I, World,
+        block_state::{BlockState, PERSISTENT_LEAVES_VALUE},
+        structures::TREE1,
+    },
+};
+
+const BLOCK_COUNT: usize = CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE;
+
+impl World {
+    /// Pick random blocks in every loaded chunk and run their random behaviours
+    pub fn update_random_ticks(&mut self, delta_time: f32) {
+        self.random_ticks_accumulator += delta_time * RANDOM_TICKS_PER_CHUNK;
+        let ticks = self.random_ticks_accumulator as usize;
+        self.random_ticks_accumulator -= ticks as f32;
+
+        for chunk_index in 0..self.chunks_manager.chunks.len() {
+            let chunk_blocks_pos = self.chunks_manager.chunks[chunk_index].get_pos() * CHUNK_SIZE_I;
+
+            for _ in 0..ticks {
+                let index = self.rng.next_u32() as usize % BLOCK_COUNT;
+                let local_pos = Vector3::new(
+                    index % CHUNK_SIZE,
+                    (index / CHUNK_SIZE) % CHUNK_SIZE,
+                    index / (CHUNK_SIZE * CHUNK_SIZE),
+                );
+
+                self.random_tick_block(chunk_blocks_pos + local_pos.map(|v| v as isize));
+            }
+        }
+    }
+
+    fn random_tick_block(&mut self, pos: Vector3<isize>) {
+        let Some(block_type) = self.chunks_manager.get_block_in_world(pos) else {
+            return;
+        };
+        let state = self
+            .chunks_manager
+            .get_block_state_in_world(pos)
+            .unwrap_or(BlockState::DEFAULT);
+
+        match block_type {
+            BlockType::Dirt => self.spread_grass(pos),
+            BlockType::Grass => {
+                // Grass dies without light
+                if self.is_covered_by_full_cube(pos) {
+                    self.set_block_in_world(pos, BlockType::Dirt);
+                }
+            }
+            BlockType::Leaves if state.get_value() != PERSISTENT_LEAVES_VALUE => {
+                self.decay_leaves(pos)
+            }
+            BlockType::Sapling => self.grow_sapling(pos, state),
+            BlockType::Carrots => self.grow_crop(pos, block_type, state),
+            _ => {}
+        }
+    }
+
+    fn is_covered_by_full_cube(&self, pos: Vector3<isize>) -> bool {
+        self.chunks_manager
+            .get_block_in_world(pos + Vector3::new(0, 1, 0))
+            .is_some_and(|b| b.is_full_cube())
+    }
+
+    /// Turn the dirt into grass if there is grass around it
+    fn spread_grass(&mut self, pos: Vector3<isize>) {
+        if self.is_covered_by_full_cube(pos) {
+            return;
+        }
+
+        for x in -1..=1 {
+            for y in -1..=1 {
+                for z in -1..=1 {
+                    if self
+                        .chunks_manager
+                        .get_block_in_world(pos + Vector3::new(x, y, z))
+                        .is_some_and(|b| b == BlockType::Grass)
+                    {
+                        self.set_block_in_world(pos, BlockType::Grass);
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Remove the leaves if there is no log near them. Can drop a sapling or an apple.
+    pub(super) fn decay_leaves(&mut self, pos: Vector3<isize>) {
+        for x in -LEAVES_DECAY_LOG_DISTANCE..=LEAVES_DECAY_LOG_DISTANCE {
+            for y in -LEAVES_DECAY_LOG_DISTANCE..=LEAVES_DECAY_LOG_DISTANCE {
+                for z in -LEAVES_DECAY_LOG_DISTANCE..=LEAVES_DECAY_LOG_DISTANCE {
+                    // Unloaded blocks may be logs
+                    if self
+                        .chunks_manager
+                        .get_block_in_world(pos + Vector3::new(x, y, z))
+                        .is_none_or(|b| b == BlockType::Log)
+                    {
+                        return;
+                    }
+                }
+            }
+        }
+
+        self.set_block_in_world(pos, BlockType::Air);
+
+        if self.rng.next_u32().is_multiple_of(SAPLING_DROP_CHANCE) {
+            self.spawn_item_entity(
+                pos.map(|v| v as f32 + 0.5),
+                ItemStack::new(ItemType::SaplingBlock, 1, false),
+            );
+        } else if self.rng.next_u32().is_multiple_of(APPLE_DROP_CHANCE) {
+            self.spawn_item_entity(
+                pos.map(|v| v as f32 + 0.5),
+                ItemStack::new(ItemType::Apple, 1, false),
+            );
+        }
+    }
+
+    /// Advance the crop to its next growth stage. Fully grown crops drop more items.
+    fn grow_crop(&mut self, pos: Vector3<isize>, block_type: BlockType, state: BlockState) {
+        let stage = state.get_value() + 1;
+
+        if stage < CROP_GROWTH_STAGES {
+            self.chunks_manager.set_block_in_world_with_state(
+                pos,
+                block_type,
+                state.with_value(stage),
+            );
+        }
+    }
+
+    /// Make the sapling grow and turn it into a tree when it is fully grown
+    fn grow_sapling(&mut self, pos: Vector3<isize>, state: BlockState) {
+        let stage = state.get_value() + 1;
+
+        if stage < SAPLING_GROWTH_STAGES {
+            self.chunks_manager.set_block_in_world_with_state(
+                pos,
+                BlockType::Sapling,
+                state.with_value(stage),
+            );
+            return;
+        }
+
+        // The sapling must be removed to leave space for the trunk
+        self.chunks_manager.set_block_in_world(pos, BlockType::Air);
+
+        if !self.world_generator.place_struct_check_space(
+            &mut self.chunks_manager,
+            &TREE1,
+            pos - Vector3::new(1, 0, 1),
+            Vector3::new(1, 0, 1),
+        ) {
+            self.chunks_manager
+                .set_block_in_world_with_state(pos, BlockType::Sapling, state);
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use nalgebra::Vector3;
+use rand_core::RngCore;
+
+use crate::{
+    constants::{
+        BlockType,
+        world::{
+            BLOCK_TICK_DURATION, FALLING_BLOCK_DELAY, LEAVES_DECAY_MAX_DELAY, TNT_FUSE_DURATION,
+        },
+    },
+    renderer::mesh::QuadDir,
+    world::{
+        World,
+        block_state::{BlockState, PERSISTENT_LEAVES_VALUE},
+    },
+};
+
+calc_use!(alloc::vec::Vec);
+
+/// A block tick requested by a block to itself
+pub struct ScheduledTick {
+    pos: Vector3<isize>,
+    tick: u32,
+}
+
+impl World {
+    /// Request an update of the block at the given position and of its neighbors
+    pub fn notify_neighbors(&mut self, pos: Vector3<isize>) {
+        self.request_block_update(pos);
+        for dir in QuadDir::ALL {
+            self.request_block_update(pos + dir.get_normal_vector());
+        }
+    }
+
+    /// The flag in the chunk keeps a block from being queued twice. The blocks of unloaded chunks are not updated.
+    fn request_block_update(&mut self, pos: Vector3<isize>) {
+        if self.chunks_manager.flag_pending_update_in_world(pos) {
+            self.pending_block_updates.push(pos);
+        }
+    }
+
+    /// Request a tick of the block at the given position in `delay` block ticks
+    pub fn schedule_block_tick(&mut self, pos: Vector3<isize>, delay: u32) {
+        let tick = self.current_block_tick + delay.max(1);
+        if !self
+            .scheduled_ticks
+            .iter()
+            .any(|scheduled| scheduled.pos == pos && scheduled.tick == tick)
+        {
+            self.scheduled_ticks.push(ScheduledTick { pos, tick });
+        }
+    }
+
+    /// Run the scheduled block ticks and the pending neighbor updates.
+    /// The updates requested while processing are delayed to the next frame.
+    pub fn update_blocks(&mut self, delta_time: f32) {
+        self.block_tick_accumulator += delta_time;
+        while self.block_tick_accumulator >= BLOCK_TICK_DURATION {
+            self.block_tick_accumulator -= BLOCK_TICK_DURATION;
+            self.current_block_tick += 1;
+
+            let current_tick = self.current_block_tick;
+            let mut due_ticks = Vec::new();
+            self.scheduled_ticks.retain(|scheduled| {
+                if scheduled.tick <= current_tick {
+                    due_ticks.push(scheduled.pos);
+                    false
+                } else {
+                    true
+                }
+            });
+
+            for pos in due_ticks {
+                self.on_scheduled_tick(pos);
+            }
+        }
+
+        let updates = core::mem::take(&mut self.pending_block_updates);
+        for pos in updates.iter() {
+            self.chunks_manager.clear_pending_update_in_world(*pos);
+        }
+        for pos in updates {
+            self.on_neighbor_update(pos);
+        }
+    }
+
+    /// Called when the block or one of its neighbors changed
+    fn on_neighbor_update(&mut self, pos: Vector3<isize>) {
+        let Some(block_type) = self.chunks_manager.get_block_in_world(pos) else {
+            return;
+        };
+        let state = self
+            .chunks_manager
+            .get_block_state_in_world(pos)
+            .unwrap_or(BlockState::DEFAULT);
+        let block_below = self
+            .chunks_manager
+            .get_block_in_world(pos - Vector3::new(0, 1, 0));
+
+        if block_type.has_gravity() && block_below.is_some_and(|b| b.is_air()) {
+            self.schedule_block_tick(pos, FALLING_BLOCK_DELAY);
+            return;
+        }
+
+        // Saplings and redstone components break when their support is removed
+        if block_below.is_some_and(|below| !block_type.can_be_placed_on(below)) {
+            self.replace_block_and_drop_item(pos, BlockType::Air);
+            return;
+        }
+
+        if block_type.is_redstone_component() {
+            self.update_redstone_component(pos, block_type, state);
+            return;
+        }
+
+        match block_type {
+            BlockType::Tnt if self.is_receiving_redstone_power(pos) => {
+                self.prime_tnt(pos, TNT_FUSE_DURATION);
+            }
+            BlockType::Leaves if state.get_value() != PERSISTENT_LEAVES_VALUE => {
+                // Spread the decay over time
+                let delay = 1 + self.rng.next_u32() % LEAVES_DECAY_MAX_DELAY;
+                self.schedule_block_tick(pos, delay);
+            }
+            _ => {}
+        }
+    }
+
+    /// Called when a tick scheduled by the block is reached
+    fn on_scheduled_tick(&mut self, pos: Vector3<isize>) {
+        let Some(block_type) = self.chunks_manager.get_block_in_world(pos) else {
+            return;
+        };
+        let state = self
+            .chunks_manager
+            .get_block_state_in_world(pos)
+            .unwrap_or(BlockState::DEFAULT);
+
+        if block_type.has_gravity() {
+            self.update_falling_block(pos);
+            return;
+        }
+
+        if block_type.is_redstone_component() {
+            self.on_redstone_tick(pos, block_type, state);
+        } else if block_type == BlockType::Leaves && state.get_value() != PERSISTENT_LEAVES_VALUE {
+            self.decay_leaves(pos);
+        }
+    }
+}
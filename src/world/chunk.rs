@@ -1,6 +1,7 @@
 use crate::{
     constants::{BlockType, world::*},
     renderer::mesh::Mesh,
+    world::block_state::BlockState,
 };
 
 use nalgebra::Vector3;
@@ -10,6 +11,9 @@ const CHUNK_SIZE_I: isize = CHUNK_SIZE as isize;
 
 pub struct Chunk {
     blocks: [BlockType; BLOCK_COUNT],
+    states: [BlockState; BLOCK_COUNT],
+    /// One bit per block, set while a neighbor update of the block is pending
+    pending_updates: [u64; BLOCK_COUNT / 64],
     pos: Vector3<isize>,
     pub mesh: Mesh,
     pub generated: bool,
@@ -22,6 +26,8 @@ impl Chunk {
     pub fn new(pos: Vector3<isize>) -> Self {
         Chunk {
             blocks: [BlockType::Air; BLOCK_COUNT],
+            states: [BlockState::DEFAULT; BLOCK_COUNT],
+            pending_updates: [0; BLOCK_COUNT / 64],
             pos,
             mesh: Mesh::new(),
             generated: false,
@@ -30,9 +36,30 @@ impl Chunk {
         }
     }
 
+    /// Set the block type at the given position. The state of the block is reset.
     pub fn set_at(&mut self, pos: Vector3<usize>, block_type: BlockType) -> bool {
+        self.set_at_with_state(pos, block_type, BlockState::DEFAULT)
+    }
+
+    pub fn set_at_with_state(
+        &mut self,
+        pos: Vector3<usize>,
+        block_type: BlockType,
+        state: BlockState,
+    ) -> bool {
+        if pos.x < CHUNK_SIZE && pos.y < CHUNK_SIZE && pos.z < CHUNK_SIZE {
+            let index = pos.x + pos.y * CHUNK_SIZE + pos.z * CHUNK_SIZE * CHUNK_SIZE;
+            self.blocks[index] = block_type;
+            self.states[index] = state;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn set_state_at(&mut self, pos: Vector3<usize>, state: BlockState) -> bool {
         if pos.x < CHUNK_SIZE && pos.y < CHUNK_SIZE && pos.z < CHUNK_SIZE {
-            self.blocks[pos.x + pos.y * CHUNK_SIZE + pos.z * CHUNK_SIZE * CHUNK_SIZE] = block_type;
+            self.states[pos.x + pos.y * CHUNK_SIZE + pos.z * CHUNK_SIZE * CHUNK_SIZE] = state;
             true
         } else {
             false
@@ -60,6 +87,24 @@ impl Chunk {
         self.blocks[(pos.x + pos.y * CHUNK_SIZE_I + pos.z * CHUNK_SIZE_I * CHUNK_SIZE_I) as usize]
     }
 
+    pub fn get_state_at_unchecked(&self, pos: Vector3<isize>) -> BlockState {
+        self.states[(pos.x + pos.y * CHUNK_SIZE_I + pos.z * CHUNK_SIZE_I * CHUNK_SIZE_I) as usize]
+    }
+
+    /// Flag the block for a neighbor update. Return false if it was already flagged.
+    pub fn flag_pending_update(&mut self, pos: Vector3<isize>) -> bool {
+        let index = (pos.x + pos.y * CHUNK_SIZE_I + pos.z * CHUNK_SIZE_I * CHUNK_SIZE_I) as usize;
+        let bit = 1 << (index % 64);
+        let already_flagged = self.pending_updates[index / 64] & bit != 0;
+        self.pending_updates[index / 64] |= bit;
+        !already_flagged
+    }
+
+    pub fn clear_pending_update(&mut self, pos: Vector3<isize>) {
+        let index = (pos.x + pos.y * CHUNK_SIZE_I + pos.z * CHUNK_SIZE_I * CHUNK_SIZE_I) as usize;
+        self.pending_updates[index / 64] &= !(1 << (index % 64));
+    }
+
     pub fn get_pos(&self) -> &Vector3<isize> {
         &self.pos
     }
@@ -68,6 +113,10 @@ impl Chunk {
         &self.blocks
     }
 
+    pub fn get_all_states(&self) -> &[BlockState; BLOCK_COUNT] {
+        &self.states
+    }
+
     pub fn get_mesh(&mut self) -> &mut Mesh {
         &mut self.mesh
     }
@@ -2,14 +2,44 @@ use libm::roundf;
 use nalgebra::Vector3;
 
 use crate::{
-    constants::{BlockType, world::CHUNK_SIZE},
+    constants::{
+        BlockType,
+        world::{CHUNK_SIZE, MAX_LIGHT_LEVEL, MAX_LIGHT_SEARCH_BLOCKS},
+    },
     misc::{div_floor, mod_floor},
     renderer::mesh::Mesh,
-    world::{CHUNK_SIZE_I, chunk::Chunk, world_generator::WorldGenerator},
+    world::{
+        CHUNK_SIZE_I,
+        block_shape::{MAX_BLOCK_BOXES, get_block_boxes},
+        block_state::BlockState,
+        chunk::Chunk,
+        world_generator::WorldGenerator,
+    },
 };
 
 calc_use!(alloc::vec::Vec);
 
+/// The blocks the sky light spreads to from a block
+const NEIGHBOR_OFFSETS: [Vector3<i8>; 6] = [
+    Vector3::new(1, 0, 0),
+    Vector3::new(-1, 0, 0),
+    Vector3::new(0, 1, 0),
+    Vector3::new(0, -1, 0),
+    Vector3::new(0, 0, 1),
+    Vector3::new(0, 0, -1),
+];
+
+/// The farthest block the sky light can come from, in blocks from the lit block
+const LIGHT_SEARCH_RADIUS: isize = MAX_LIGHT_LEVEL as isize - 1;
+/// The width of the cube of blocks searched for the sky light
+const LIGHT_SEARCH_WIDTH: usize = 2 * LIGHT_SEARCH_RADIUS as usize + 1;
+
+/// Return the index in the visited bitset of the block at the given offset from the lit block
+fn get_light_search_index(offset: Vector3<i8>) -> usize {
+    let local = offset.map(|v| (v as isize + LIGHT_SEARCH_RADIUS) as usize);
+    (local.x * LIGHT_SEARCH_WIDTH + local.y) * LIGHT_SEARCH_WIDTH + local.z
+}
+
 /// Convert the block position from world space to chunk space
 pub fn get_chunk_local_coords(pos: Vector3<isize>) -> Vector3<isize> {
     Vector3::new(
@@ -21,11 +51,29 @@ pub fn get_chunk_local_coords(pos: Vector3<isize>) -> Vector3<isize> {
 
 pub struct ChunksManager {
     pub chunks: Vec<Chunk>,
+    batch_update_chunks: Option<Vec<Vector3<isize>>>,
 }
 
 impl ChunksManager {
     pub fn new() -> Self {
-        ChunksManager { chunks: Vec::with_capacity(4 * 4 * 4) }
+        ChunksManager {
+            chunks: Vec::with_capacity(4 * 4 * 4),
+            batch_update_chunks: None,
+        }
+    }
+
+    /// Delay the mesh regeneration requests until end_batch_update is called. Used when a lot of blocks are modified at once.
+    pub fn begin_batch_update(&mut self) {
+        self.batch_update_chunks = Some(Vec::new());
+    }
+
+    /// Request the regeneration of all the chunks modified since begin_batch_update
+    pub fn end_batch_update(&mut self) {
+        if let Some(chunks) = self.batch_update_chunks.take() {
+            for pos in chunks {
+                self.request_mesh_regen_if_exists(pos);
+            }
+        }
     }
 
     /// Return the chunk at the given position. Return an Option containing a MUTABLE reference to the chunk
@@ -93,8 +141,116 @@ impl ChunksManager {
             .map(|chunk| chunk.get_at_unchecked(get_chunk_local_coords(pos)))
     }
 
+    /// Flag the block at the given position in world blocks space for a neighbor update.
+    /// Return false if it was already flagged or if its chunk is not loaded.
+    pub fn flag_pending_update_in_world(&mut self, pos: Vector3<isize>) -> bool {
+        let chunk_pos = Vector3::new(
+            div_floor(pos.x, CHUNK_SIZE_I),
+            div_floor(pos.y, CHUNK_SIZE_I),
+            div_floor(pos.z, CHUNK_SIZE_I),
+        );
+        self.get_chunk_at_pos_mut(chunk_pos)
+            .is_some_and(|chunk| chunk.flag_pending_update(get_chunk_local_coords(pos)))
+    }
+
+    pub fn clear_pending_update_in_world(&mut self, pos: Vector3<isize>) {
+        let chunk_pos = Vector3::new(
+            div_floor(pos.x, CHUNK_SIZE_I),
+            div_floor(pos.y, CHUNK_SIZE_I),
+            div_floor(pos.z, CHUNK_SIZE_I),
+        );
+        if let Some(chunk) = self.get_chunk_at_pos_mut(chunk_pos) {
+            chunk.clear_pending_update(get_chunk_local_coords(pos));
+        }
+    }
+
+    /// Return the state of the block at the given position in world blocks space
+    pub fn get_block_state_in_world(&self, pos: Vector3<isize>) -> Option<BlockState> {
+        let chunk_pos = Vector3::new(
+            div_floor(pos.x, CHUNK_SIZE_I),
+            div_floor(pos.y, CHUNK_SIZE_I),
+            div_floor(pos.z, CHUNK_SIZE_I),
+        );
+        self.get_chunk_at_pos(chunk_pos)
+            .map(|chunk| chunk.get_state_at_unchecked(get_chunk_local_coords(pos)))
+    }
+
+    /// Return the light received from the sky at the given position in world blocks space.
+    /// The blocks under the open sky are fully lit. The light spreads from them through the blocks that are not
+    /// full cubes and loses one level per block, so the shade of a tree stays lit but a cave gets dark.
+    pub fn get_sky_light(&self, pos: Vector3<isize>) -> u8 {
+        // One bit per block around the position, set once the block is queued
+        let mut visited = [0u32; LIGHT_SEARCH_WIDTH.pow(3).div_ceil(32)];
+        // The offsets of the blocks to check with the light they would get, nearest first
+        let mut queue: heapless::Vec<(Vector3<i8>, u8), MAX_LIGHT_SEARCH_BLOCKS> =
+            heapless::Vec::new();
+
+        let start = Vector3::zeros();
+        let index = get_light_search_index(start);
+        visited[index / 32] |= 1 << (index % 32);
+        let _ = queue.push((start, MAX_LIGHT_LEVEL));
+
+        let mut next = 0;
+        while let Some(&(offset, light)) = queue.get(next) {
+            next += 1;
+
+            let block_pos = pos + offset.map(isize::from);
+            if self.is_under_open_sky(block_pos) {
+                return light;
+            }
+            if light == 1 {
+                continue;
+            }
+
+            for neighbor_offset in NEIGHBOR_OFFSETS {
+                let neighbor = offset + neighbor_offset;
+                let index = get_light_search_index(neighbor);
+                if visited[index / 32] & (1 << (index % 32)) == 0
+                    && self
+                        .get_block_in_world(pos + neighbor.map(isize::from))
+                        .is_some_and(|block| !block.is_full_cube())
+                    && queue.push((neighbor, light - 1)).is_ok()
+                {
+                    visited[index / 32] |= 1 << (index % 32);
+                }
+            }
+        }
+        0
+    }
+
+    /// Return true if there is no full cube above the given position in world blocks space
+    fn is_under_open_sky(&self, pos: Vector3<isize>) -> bool {
+        let mut above = pos + Vector3::new(0, 1, 0);
+        while let Some(block_type) = self.get_block_in_world(above) {
+            if block_type.is_full_cube() {
+                return false;
+            }
+            above.y += 1;
+        }
+        true
+    }
+
+    /// Return the indexes in BLOCK_BOXES of the boxes composing the block at the given position in world blocks space
+    pub fn get_block_boxes_in_world(
+        &self,
+        pos: Vector3<isize>,
+    ) -> Option<heapless::Vec<u8, MAX_BLOCK_BOXES>> {
+        let block_type = self.get_block_in_world(pos)?;
+        let block_state = self.get_block_state_in_world(pos)?;
+        Some(get_block_boxes(block_type, block_state, |dir| {
+            self.get_block_in_world(pos + dir.get_normal_vector())
+        }))
+    }
+
     /// Request the regeneration of the chunk mesh if this chunk is already loaded
     pub fn request_mesh_regen_if_exists(&mut self, pos: Vector3<isize>) {
+        if let Some(batch_update_chunks) = &mut self.batch_update_chunks {
+            if !batch_update_chunks.contains(&pos) {
+                batch_update_chunks.push(pos);
+            }
+            return;
+        }
+
         if let Some(chunk) = self.get_chunk_at_pos_mut(pos) {
             chunk.need_new_mesh = true;
         }
@@ -102,6 +258,16 @@ impl ChunksManager {
 
     /// Set the block type of the block at the given position in world blocks space. Regenerate chunk mesh if needed
     pub fn set_block_in_world(&mut self, pos: Vector3<isize>, block_type: BlockType) -> bool {
+        self.set_block_in_world_with_state(pos, block_type, BlockState::DEFAULT)
+    }
+
+    /// Set the block type and the state of the block at the given position in world blocks space. Regenerate chunk mesh if needed
+    pub fn set_block_in_world_with_state(
+        &mut self,
+        pos: Vector3<isize>,
+        block_type: BlockType,
+        state: BlockState,
+    ) -> bool {
         let chunk_pos = Vector3::new(
             div_floor(pos.x, CHUNK_SIZE_I),
             div_floor(pos.y, CHUNK_SIZE_I),
@@ -109,27 +275,8 @@ impl ChunksManager {
         );
         if let Some(chunk) = self.get_chunk_at_pos_mut(chunk_pos) {
             let local_pos = get_chunk_local_coords(pos);
-            if chunk.set_at(local_pos.map(|x| x as usize), block_type) {
-                chunk.need_new_mesh = true;
-
-                if local_pos.x == 0 {
-                    self.request_mesh_regen_if_exists(chunk_pos + Vector3::new(-1, 0, 0));
-                }
-                if local_pos.x == CHUNK_SIZE_I - 1 {
-                    self.request_mesh_regen_if_exists(chunk_pos + Vector3::new(1, 0, 0));
-                }
-                if local_pos.y == 0 {
-                    self.request_mesh_regen_if_exists(chunk_pos + Vector3::new(0, -1, 0));
-                }
-                if local_pos.y == CHUNK_SIZE_I - 1 {
-                    self.request_mesh_regen_if_exists(chunk_pos + Vector3::new(0, 1, 0));
-                }
-                if local_pos.z == 0 {
-                    self.request_mesh_regen_if_exists(chunk_pos + Vector3::new(0, 0, -1));
-                }
-                if local_pos.z == CHUNK_SIZE_I - 1 {
-                    self.request_mesh_regen_if_exists(chunk_pos + Vector3::new(0, 0, 1));
-                }
+            if chunk.set_at_with_state(local_pos.map(|x| x as usize), block_type, state) {
+                self.request_mesh_regen_around(chunk_pos, local_pos);
                 true
             } else {
                 false
@@ -139,6 +286,30 @@ impl ChunksManager {
         }
     }
 
+    /// Request the regeneration of the chunk mesh and of the neighbor chunks if the block is on the border
+    fn request_mesh_regen_around(&mut self, chunk_pos: Vector3<isize>, local_pos: Vector3<isize>) {
+        self.request_mesh_regen_if_exists(chunk_pos);
+
+        if local_pos.x == 0 {
+            self.request_mesh_regen_if_exists(chunk_pos + Vector3::new(-1, 0, 0));
+        }
+        if local_pos.x == CHUNK_SIZE_I - 1 {
+            self.request_mesh_regen_if_exists(chunk_pos + Vector3::new(1, 0, 0));
+        }
+        if local_pos.y == 0 {
+            self.request_mesh_regen_if_exists(chunk_pos + Vector3::new(0, -1, 0));
+        }
+        if local_pos.y == CHUNK_SIZE_I - 1 {
+            self.request_mesh_regen_if_exists(chunk_pos + Vector3::new(0, 1, 0));
+        }
+        if local_pos.z == 0 {
+            self.request_mesh_regen_if_exists(chunk_pos + Vector3::new(0, 0, -1));
+        }
+        if local_pos.z == CHUNK_SIZE_I - 1 {
+            self.request_mesh_regen_if_exists(chunk_pos + Vector3::new(0, 0, 1));
+        }
+    }
+
     /// Generate the chunks around the given position The position is in global blocks space, not world chunk space
     pub fn generate_world_around_pos(
         &mut self,
@@ -0,0 +1,109 @@
+use libm::{ceilf, floorf};
+use nalgebra::Vector3;
+use rand_core::RngCore;
+
+use crate::{
+    constants::{
+        BlockType, EntityType,
+        world::{
+            EXPLOSION_DROP_CHANCE, EXPLOSION_KNOCKBACK, TNT_CHAIN_MAX_FUSE_DURATION,
+            TNT_CHAIN_MIN_FUSE_DURATION, TNT_EXPLOSION_POWER, TNT_PRIMING_JUMP_VELOCITY,
+        },
+    },
+    entity::{EntityData, primed_tnt::PrimedTntData},
+    misc::random_f32,
+    world::World,
+};
+
+calc_use!(alloc::vec::Vec);
+
+impl World {
+    /// Replace the TNT block at the given position by a primed TNT entity that explodes after `fuse` seconds
+    pub fn prime_tnt(&mut self, pos: Vector3<isize>, fuse: f32) {
+        self.set_block_in_world(pos, BlockType::Air);
+
+        let id = self.spawn_entity_auto(
+            EntityType::PrimedTnt,
+            pos.map(|v| v as f32 + 0.5),
+            EntityData::PrimedTnt(PrimedTntData { fuse }),
+        );
+        if let Some(entity) = self.get_entity_by_id_mut(id) {
+            entity.velocity.y = TNT_PRIMING_JUMP_VELOCITY;
+        }
+    }
+
+    /// Make the primed TNT fuses burn and explode the ones that reached the end
+    pub(super) fn update_primed_tnt(&mut self, delta_time: f32) {
+        let mut exploded = Vec::new();
+
+        for entity in self.loaded_entities.iter_mut() {
+            if entity.get_type() == EntityType::PrimedTnt
+                && let Some(primed_tnt_data) = PrimedTntData::get_primed_tnt_data_mut(entity)
+            {
+                primed_tnt_data.fuse -= delta_time;
+                if primed_tnt_data.fuse <= 0. {
+                    exploded.push((entity.get_id(), entity.pos));
+                }
+            }
+        }
+
+        for (id, pos) in exploded {
+            self.remove_entity(id);
+            self.explode(pos, TNT_EXPLOSION_POWER);
+        }
+    }
+
+    /// Destroy the blocks around the center depending of their blast resistance and push the entities away
+    pub fn explode(&mut self, center: Vector3<f32>, power: f32) {
+        let radius = ceilf(power) as isize;
+        let center_block = center.map(|v| floorf(v) as isize);
+
+        // All the modified chunks are regenerated once at the end
+        self.chunks_manager.begin_batch_update();
+
+        for x in -radius..=radius {
+            for y in -radius..=radius {
+                for z in -radius..=radius {
+                    let pos = center_block + Vector3::new(x, y, z);
+                    let Some(block_type) = self.chunks_manager.get_block_in_world(pos) else {
+                        continue;
+                    };
+                    if block_type.is_air() {
+                        continue;
+                    }
+
+                    // The random variation gives an irregular shape to the crater
+                    let distance = (pos.map(|v| v as f32 + 0.5) - center).norm();
+                    let strength = power * (0.7 + 0.6 * random_f32(&mut self.rng)) - distance;
+                    if strength <= block_type.get_blast_resistance() {
+                        continue;
+                    }
+
+                    if block_type == BlockType::Tnt {
+                        let fuse = TNT_CHAIN_MIN_FUSE_DURATION
+                            + (TNT_CHAIN_MAX_FUSE_DURATION - TNT_CHAIN_MIN_FUSE_DURATION)
+                                * random_f32(&mut self.rng);
+                        self.prime_tnt(pos, fuse);
+                    } else if self.rng.next_u32().is_multiple_of(EXPLOSION_DROP_CHANCE) {
+                        self.replace_block_and_drop_item(pos, BlockType::Air);
+                    } else {
+                        self.set_block_in_world(pos, BlockType::Air);
+                    }
+                }
+            }
+        }
+
+        self.chunks_manager.end_batch_update();
+
+        // Knockback
+        let knockback_distance = power * 2.;
+        for entity in self.loaded_entities.iter_mut() {
+            let offset = entity.pos - center;
+            let distance = offset.norm();
+            if distance > 0. && distance < knockback_distance {
+                entity.velocity +=
+                    offset / distance * EXPLOSION_KNOCKBACK * (1. - distance / knockback_distance);
+            }
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use nalgebra::Vector3;
+
+use crate::{
+    constants::{BlockType, ItemType, world::SMELTING_DURATION},
+    inventory::{Inventory, ItemStack},
+    world::{RegisteredInventory, World, block_state::BlockState},
+};
+
+calc_use!(alloc::vec::Vec);
+
+pub const FURNACE_INPUT_SLOT: usize = 0;
+pub const FURNACE_FUEL_SLOT: usize = 1;
+pub const FURNACE_OUTPUT_SLOT: usize = 2;
+
+/// Furnaces with this value are burning fuel
+pub const LIT_FURNACE_VALUE: u8 = 1;
+
+struct SmeltingRecipe {
+    input: ItemType,
+    result: ItemStack,
+}
+
+impl SmeltingRecipe {
+    pub const fn new(data: &'static [u8]) -> Self {
+        // 1 byte : input
+        let input = ItemType::get_from_id(data[0]).expect("Invalid item id in smelting recipe.");
+
+        // 1 byte : result
+        let result_type =
+            ItemType::get_from_id(data[1]).expect("Invalid item id in smelting recipe.");
+
+        // 1 byte : result amount
+        let result_amount = data[2];
+
+        SmeltingRecipe {
+            input,
+            result: ItemStack::new(result_type, result_amount, false),
+        }
+    }
+}
+
+// The table is generated by build.rs from the smelting directory
+include!("../../target/smelting/smelting.rs");
+
+/// The timers of a furnace placed in the world. Its slots are stored in a registered inventory.
+pub struct FurnaceState {
+    pub pos: Vector3<isize>,
+    /// Remaining burning time of the current fuel, in seconds
+    pub burn_time: f32,
+    /// Total burning time of the current fuel, in seconds
+    pub max_burn_time: f32,
+    /// Time spent smelting the current input, in seconds
+    pub cook_time: f32,
+}
+
+impl FurnaceState {
+    pub fn new(pos: Vector3<isize>) -> Self {
+        FurnaceState {
+            pos,
+            burn_time: 0.,
+            max_burn_time: 0.,
+            cook_time: 0.,
+        }
+    }
+
+    /// Return the smelting progress of the current input, between 0 and 1
+    pub fn get_cook_progress(&self) -> f32 {
+        (self.cook_time / SMELTING_DURATION).min(1.)
+    }
+
+    /// Return the part of the current fuel that remains, between 0 and 1
+    pub fn get_burn_progress(&self) -> f32 {
+        if self.max_burn_time > 0. {
+            (self.burn_time / self.max_burn_time).clamp(0., 1.)
+        } else {
+            0.
+        }
+    }
+
+    /// Burn the fuel and smelt the input. Return true if the furnace is burning.
+    fn update(&mut self, inventory: &mut Inventory, delta_time: f32) -> bool {
+        let result = Self::get_smelting_result(inventory);
+
+        // Light new fuel only if there is something to smelt
+        if self.burn_time <= 0.
+            && result.is_some()
+            && let Some(burn_duration) = inventory
+                .get_item_type_at_slot_index(FURNACE_FUEL_SLOT)
+                .and_then(|fuel| fuel.get_burn_duration())
+        {
+            inventory.take_one(FURNACE_FUEL_SLOT);
+            self.burn_time = burn_duration;
+            self.max_burn_time = burn_duration;
+        }
+
+        if self.burn_time <= 0. {
+            // The input cools down when there is no fuel
+            self.cook_time = (self.cook_time - delta_time * 2.).max(0.);
+            return false;
+        }
+
+        self.burn_time -= delta_time;
+
+        if let Some(result) = result {
+            self.cook_time += delta_time;
+            if self.cook_time >= SMELTING_DURATION {
+                self.cook_time = 0.;
+                inventory.take_one(FURNACE_INPUT_SLOT);
+                inventory.replace_slot_item_stack(FURNACE_OUTPUT_SLOT, result);
+            }
+        } else {
+            self.cook_time = 0.;
+        }
+
+        true
+    }
+
+    /// Return the content of the output slot once the input is smelted.
+    /// Return None if the input can't be smelted or if the output slot is full.
+    fn get_smelting_result(inventory: &Inventory) -> Option<ItemStack> {
+        let input = inventory.get_item_type_at_slot_index(FURNACE_INPUT_SLOT)?;
+        let recipe = SMELTING_RECIPES
+            .iter()
+            .find(|recipe| recipe.input == input)?;
+        let output = inventory.get_item_stack_at_slot_index(FURNACE_OUTPUT_SLOT)?;
+
+        if output.get_item_type() == ItemType::Air {
+            Some(recipe.result.clone())
+        } else if output.can_stack_with(&recipe.result)
+            && output.get_amount() + recipe.result.get_amount()
+                <= output.get_item_type().get_max_stack_amount()
+        {
+            Some(output.with_amount(output.get_amount() + recipe.result.get_amount()))
+        } else {
+            None
+        }
+    }
+}
+
+impl World {
+    /// Advance the timers of all the furnaces of the loaded area
+    pub fn update_furnaces(&mut self, delta_time: f32) {
+        let mut lit_changes = Vec::new();
+
+        for furnace in self.furnaces.iter_mut() {
+            let Some(RegisteredInventory { inventory, .. }) = self
+                .registered_inventories
+                .iter_mut()
+                .find(|registered| registered.block_pos == Some(furnace.pos))
+            else {
+                continue;
+            };
+
+            let lit = furnace.update(inventory, delta_time);
+
+            let state = self
+                .chunks_manager
+                .get_block_state_in_world(furnace.pos)
+                .unwrap_or(BlockState::DEFAULT);
+            if lit != (state.get_value() == LIT_FURNACE_VALUE) {
+                let value = if lit { LIT_FURNACE_VALUE } else { 0 };
+                lit_changes.push((furnace.pos, state.with_value(value)));
+            }
+        }
+
+        // Only the mesh needs to be updated, the neighbors don't depend on the furnace state
+        for (pos, state) in lit_changes {
+            self.chunks_manager
+                .set_block_in_world_with_state(pos, BlockType::Furnace, state);
+        }
+    }
+
+    /// Return the timers of the furnace at the given position
+    pub fn get_furnace_state(&self, pos: Vector3<isize>) -> Option<&FurnaceState> {
+        self.furnaces.iter().find(|furnace| furnace.pos == pos)
+    }
+}
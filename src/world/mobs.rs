@@ -0,0 +1,237 @@
+use libm::{floorf, sincosf};
+use nalgebra::Vector3;
+use rand_core::RngCore;
+
+use crate::{
+    constants::{
+        EntityType,
+        mob::{
+            CONTACT_ATTACK_INTERVAL, CONTACT_KNOCKBACK, DESPAWN_DISTANCE, GENERATION_GROUP_CHANCE,
+            GROUP_SPREAD, MAX_GROUP_SIZE, MAX_HOSTILE_MOBS, MAX_PASSIVE_MOBS, MAX_SPAWN_DISTANCE,
+            MIN_SPAWN_DISTANCE, PATHFINDING_BUDGET, SPAWN_INTERVAL,
+        },
+        player::VOID_HEIGHT,
+    },
+    entity::{
+        EntityData,
+        mob::{MobContext, MobData},
+    },
+    inventory::ItemStack,
+    misc::random_f32,
+    world::{CHUNK_SIZE_I, World},
+};
+
+calc_use!(alloc::vec::Vec);
+
+/// The mobs picked by the natural spawning
+const SPAWNABLE_MOBS: [EntityType; 2] = [EntityType::Pig, EntityType::Zombie];
+
+impl World {
+    /// Move the mobs, kill the ones without health, despawn the ones far from the player and spawn new ones
+    pub(super) fn update_mobs(&mut self, delta_time: f32) {
+        let player_pos = self.get_player_entity().pos;
+        let Some((player, entities)) = self.loaded_entities.split_first_mut() else {
+            return;
+        };
+        let mut context = MobContext {
+            chunks_manager: &self.chunks_manager,
+            player,
+            rng: &mut self.rng,
+            pathfinding_budget: PATHFINDING_BUDGET,
+        };
+
+        // Hurt mobs are pushed back instead of walking
+        for entity in entities.iter_mut() {
+            if let Some(behavior) = entity.get_type().get_mob_behavior()
+                && !entity.is_hurt()
+            {
+                behavior.update(entity, &mut context, delta_time);
+            }
+        }
+
+        self.update_contact_attacks(delta_time);
+
+        // Killed mobs drop their loot, the others just disappear
+        let mut killed = Vec::new();
+        self.loaded_entities.retain(|entity| {
+            if entity.get_type().get_mob_behavior().is_none() {
+                return true;
+            }
+            if entity.health == Some(0) {
+                killed.push((entity.get_type(), entity.pos));
+                return false;
+            }
+            entity.pos.y >= VOID_HEIGHT
+                && entity.pos.metric_distance(&player_pos) <= DESPAWN_DISTANCE
+        });
+
+        for (entity_type, pos) in killed {
+            if let Some(behavior) = entity_type.get_mob_behavior() {
+                let (item_type, max_amount) = behavior.get_drop();
+                let amount = 1 + (self.rng.next_u32() % max_amount as u32) as u8;
+                self.spawn_item_entity(pos, ItemStack::new(item_type, amount, false));
+            }
+        }
+
+        self.mob_spawn_accumulator += delta_time;
+        while self.mob_spawn_accumulator >= SPAWN_INTERVAL {
+            self.mob_spawn_accumulator -= SPAWN_INTERVAL;
+            self.try_natural_spawn(player_pos);
+        }
+    }
+
+    /// Hurt the player touching a hostile mob and push it away
+    fn update_contact_attacks(&mut self, delta_time: f32) {
+        let Some((player, entities)) = self.loaded_entities.split_first_mut() else {
+            return;
+        };
+        let Some(player_bbox) = player.get_bbox() else {
+            return;
+        };
+
+        for entity in entities.iter_mut() {
+            let Some(behavior) = entity.get_type().get_mob_behavior() else {
+                continue;
+            };
+            let damage = behavior.get_contact_damage();
+            let pos = entity.pos;
+            let is_touching = entity
+                .get_bbox()
+                .is_some_and(|bbox| bbox.is_coliding(&player_bbox));
+            let Some(mob_data) = MobData::get_mob_data_mut(entity) else {
+                continue;
+            };
+
+            mob_data.attack_cooldown -= delta_time;
+            if damage == 0 || !is_touching || mob_data.attack_cooldown > 0. {
+                continue;
+            }
+            mob_data.attack_cooldown = CONTACT_ATTACK_INTERVAL;
+
+            let mut away = (player.pos - pos).xz();
+            if away.norm() > 0. {
+                away = away.normalize() * CONTACT_KNOCKBACK;
+            }
+            player.hit(damage, Vector3::new(away.x, 0., away.y));
+        }
+    }
+
+    /// Pick a random mob in the spawn list. Return None if there is no matching mob.
+    fn pick_spawnable_mob(&mut self, passive_only: bool) -> Option<EntityType> {
+        let is_allowed = |entity_type: &&EntityType| {
+            !passive_only
+                || entity_type
+                    .get_mob_behavior()
+                    .is_some_and(|behavior| !behavior.is_hostile())
+        };
+
+        let count = SPAWNABLE_MOBS.iter().filter(is_allowed).count() as u32;
+        if count == 0 {
+            return None;
+        }
+        let index = (self.rng.next_u32() % count) as usize;
+        SPAWNABLE_MOBS.iter().filter(is_allowed).nth(index).copied()
+    }
+
+    /// Try to spawn a mob at a random distance from the player, at any height where it is allowed to spawn
+    fn try_natural_spawn(&mut self, player_pos: Vector3<f32>) {
+        let Some(entity_type) = self.pick_spawnable_mob(false) else {
+            return;
+        };
+        let Some(behavior) = entity_type.get_mob_behavior() else {
+            return;
+        };
+
+        // Passive and hostile mobs have their own limit
+        let is_hostile = behavior.is_hostile();
+        let max_mobs = if is_hostile {
+            MAX_HOSTILE_MOBS
+        } else {
+            MAX_PASSIVE_MOBS
+        };
+        let mobs_count = self
+            .loaded_entities
+            .iter()
+            .filter(|entity| {
+                entity
+                    .get_type()
+                    .get_mob_behavior()
+                    .is_some_and(|behavior| behavior.is_hostile() == is_hostile)
+            })
+            .count();
+        if mobs_count >= max_mobs {
+            return;
+        }
+
+        let angle = random_f32(&mut self.rng) * 2. * core::f32::consts::PI;
+        let distance = MIN_SPAWN_DISTANCE
+            + (MAX_SPAWN_DISTANCE - MIN_SPAWN_DISTANCE) * random_f32(&mut self.rng);
+        let (x, z) = sincosf(angle);
+
+        let x = floorf(player_pos.x + x * distance) as isize;
+        let z = floorf(player_pos.z + z * distance) as isize;
+
+        // Look for a free spot from the surface to the bottom of the world
+        for y in (0..=self.get_highest_block(x, z)).rev() {
+            if self
+                .try_spawn_mob(entity_type, Vector3::new(x, y, z))
+                .is_some()
+            {
+                return;
+            }
+        }
+    }
+
+    /// Spawn groups of mobs on the surface of the newly generated chunk columns
+    pub(super) fn spawn_generation_mobs(
+        &mut self,
+        x_start: isize,
+        x_stop: isize,
+        z_start: isize,
+        z_stop: isize,
+    ) {
+        for chunk_x in x_start..x_stop {
+            for chunk_z in z_start..z_stop {
+                if !self.rng.next_u32().is_multiple_of(GENERATION_GROUP_CHANCE) {
+                    continue;
+                }
+
+                // The surface is lit, only passive mobs can spawn there
+                let Some(entity_type) = self.pick_spawnable_mob(true) else {
+                    return;
+                };
+                let center_x =
+                    chunk_x * CHUNK_SIZE_I + (self.rng.next_u32() % CHUNK_SIZE_I as u32) as isize;
+                let center_z =
+                    chunk_z * CHUNK_SIZE_I + (self.rng.next_u32() % CHUNK_SIZE_I as u32) as isize;
+
+                let group_size = 1 + self.rng.next_u32() % MAX_GROUP_SIZE;
+                for _ in 0..group_size {
+                    let spread = 2 * GROUP_SPREAD as u32 + 1;
+                    let x = center_x + (self.rng.next_u32() % spread) as isize - GROUP_SPREAD;
+                    let z = center_z + (self.rng.next_u32() % spread) as isize - GROUP_SPREAD;
+                    self.try_spawn_mob(
+                        entity_type,
+                        Vector3::new(x, self.get_highest_block(x, z), z),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Spawn the mob with its feet in the block at the given position if its spawning rules allow it.
+    /// Return the id of the mob.
+    pub fn try_spawn_mob(&mut self, entity_type: EntityType, pos: Vector3<isize>) -> Option<usize> {
+        let behavior = entity_type.get_mob_behavior()?;
+        if !behavior.can_spawn_at(&self.chunks_manager, pos) {
+            return None;
+        }
+
+        let bbox = entity_type.get_bbox()?;
+        let feet_pos = pos.map(|v| v as f32) + Vector3::new(0.5, 0., 0.5);
+        let spawn_pos = feet_pos - Vector3::new(0., bbox.offset.y, 0.);
+
+        let id = self.spawn_entity_auto(entity_type, spawn_pos, EntityData::Mob(MobData::new()));
+        Some(id)
+    }
+}
@@ -0,0 +1,255 @@
+use libm::{ceilf, floorf};
+use nalgebra::Vector3;
+
+use crate::{
+    constants::mob::{MAX_PATH_DROP, MAX_PATH_NODES},
+    physic::BoundingBox,
+    world::{block_shape::BlockShape, chunk_manager::ChunksManager},
+};
+
+calc_use!(alloc::vec::Vec);
+calc_use!(alloc::vec);
+
+/// The horizontal moves tried from each block of the path
+const DIRECTIONS: [Vector3<isize>; 4] = [
+    Vector3::new(1, 0, 0),
+    Vector3::new(-1, 0, 0),
+    Vector3::new(0, 0, 1),
+    Vector3::new(0, 0, -1),
+];
+
+/// The space taken by an entity, in blocks
+#[derive(Clone, Copy, Debug)]
+pub struct PathSize {
+    pub width: isize,
+    pub height: isize,
+}
+
+impl PathSize {
+    /// Return the number of blocks the bounding box needs to pass
+    pub fn from_bbox(bbox: &BoundingBox) -> Self {
+        PathSize {
+            width: ceilf(bbox.size.x.max(bbox.size.z)) as isize,
+            height: ceilf(bbox.size.y) as isize,
+        }
+    }
+
+    /// Return the block where the path of an entity with the given world space bounding box starts.
+    /// Wide entities start at the lowest corner of the blocks they take.
+    pub fn get_start_pos(&self, bbox: &BoundingBox) -> Vector3<isize> {
+        let center = bbox.offset + bbox.size / 2.;
+        let half_width = (self.width - 1) as f32 / 2.;
+        Vector3::new(
+            floorf(center.x - half_width) as isize,
+            floorf(bbox.offset.y + 0.1) as isize,
+            floorf(center.z - half_width) as isize,
+        )
+    }
+
+    /// Return the position an entity following the path aims at when walking to the given block
+    pub fn get_block_center(&self, pos: Vector3<isize>) -> Vector3<f32> {
+        pos.map(|v| v as f32) + Vector3::new(self.width as f32 / 2., 0., self.width as f32 / 2.)
+    }
+}
+
+struct PathNode {
+    pos: Vector3<isize>,
+    parent: Option<usize>,
+    /// Cost of the path from the start to this node
+    cost: u16,
+    /// Estimated cost from this node to the goal
+    estimate: u16,
+    is_open: bool,
+}
+
+/// An A* search over the blocks of the world. The search is spread across several frames to fit in the CPU time.
+pub struct PathSearch {
+    goal: Vector3<isize>,
+    size: PathSize,
+    nodes: Vec<PathNode>,
+    /// The path, from the start to the goal, once the search is finished
+    result: Option<Vec<Vector3<isize>>>,
+}
+
+impl PathSearch {
+    pub fn new(start: Vector3<isize>, goal: Vector3<isize>, size: PathSize) -> Self {
+        let nodes = vec![PathNode {
+            pos: start,
+            parent: None,
+            cost: 0,
+            estimate: get_estimate(start, goal),
+            is_open: true,
+        }];
+
+        PathSearch {
+            goal,
+            size,
+            nodes,
+            result: None,
+        }
+    }
+
+    /// Explore up to `budget` nodes and remove them from the budget.
+    /// When the goal can't be reached, the search ends with the path to the nearest explored node.
+    pub fn step(&mut self, chunks_manager: &ChunksManager, budget: &mut usize) {
+        while self.result.is_none() && *budget > 0 {
+            *budget -= 1;
+
+            // Pick the open node with the lowest total cost
+            let Some(current) = self
+                .nodes
+                .iter()
+                .enumerate()
+                .filter(|(_, node)| node.is_open)
+                .min_by_key(|(_, node)| (node.cost + node.estimate, node.estimate))
+                .map(|(index, _)| index)
+            else {
+                self.finish(self.get_nearest_node());
+                return;
+            };
+
+            if self.nodes[current].pos == self.goal || self.nodes.len() >= MAX_PATH_NODES {
+                let end = if self.nodes[current].pos == self.goal {
+                    current
+                } else {
+                    self.get_nearest_node()
+                };
+                self.finish(end);
+                return;
+            }
+
+            self.nodes[current].is_open = false;
+            let pos = self.nodes[current].pos;
+            let cost = self.nodes[current].cost;
+
+            for direction in DIRECTIONS {
+                if let Some((neighbor, move_cost)) = self.get_move(chunks_manager, pos, direction) {
+                    self.add_node(neighbor, current, cost + move_cost);
+                }
+            }
+        }
+    }
+
+    /// Return the path from the start to the goal, or to the nearest reachable block, once the search is finished
+    pub fn take_result(&mut self) -> Option<Vec<Vector3<isize>>> {
+        self.result.take()
+    }
+
+    /// Return the block reached by moving in the direction and its cost. Return None if the move is impossible.
+    fn get_move(
+        &self,
+        chunks_manager: &ChunksManager,
+        pos: Vector3<isize>,
+        direction: Vector3<isize>,
+    ) -> Option<(Vector3<isize>, u16)> {
+        let target = pos + direction;
+
+        if !self.is_passable(chunks_manager, target) {
+            // Jump on the block in the way if there is room above the entity
+            let up = Vector3::new(0, 1, 0);
+            if self.is_passable(chunks_manager, pos + up)
+                && self.is_passable(chunks_manager, target + up)
+                && self.is_standable(chunks_manager, target + up)
+            {
+                return Some((target + up, 2));
+            }
+            return None;
+        }
+
+        // Walk down to the floor, up to MAX_PATH_DROP blocks below
+        for drop in 0..=MAX_PATH_DROP {
+            let below = target - Vector3::new(0, drop, 0);
+            if drop > 0 && !self.is_passable(chunks_manager, below) {
+                return None;
+            }
+            if self.is_standable(chunks_manager, below) {
+                return Some((below, 1 + drop as u16));
+            }
+        }
+        None
+    }
+
+    /// Return true if the entity fits in the blocks starting at the given position
+    fn is_passable(&self, chunks_manager: &ChunksManager, pos: Vector3<isize>) -> bool {
+        for x in 0..self.size.width {
+            for y in 0..self.size.height {
+                for z in 0..self.size.width {
+                    // Unloaded blocks are solid
+                    if !chunks_manager
+                        .get_block_in_world(pos + Vector3::new(x, y, z))
+                        .is_some_and(|block| !block.has_collision())
+                    {
+                        return false;
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    /// Return true if the entity can stand on the blocks below the given position. Fences are too high to walk on.
+    fn is_standable(&self, chunks_manager: &ChunksManager, pos: Vector3<isize>) -> bool {
+        for x in 0..self.size.width {
+            for z in 0..self.size.width {
+                if chunks_manager
+                    .get_block_in_world(pos + Vector3::new(x, -1, z))
+                    .is_some_and(|block| {
+                        block.has_collision() && block.get_shape() != BlockShape::Fence
+                    })
+                {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Add the node or update it if it was already reached by a more expensive path
+    fn add_node(&mut self, pos: Vector3<isize>, parent: usize, cost: u16) {
+        if let Some(node) = self.nodes.iter_mut().find(|node| node.pos == pos) {
+            if node.is_open && cost < node.cost {
+                node.cost = cost;
+                node.parent = Some(parent);
+            }
+            return;
+        }
+
+        self.nodes.push(PathNode {
+            pos,
+            parent: Some(parent),
+            cost,
+            estimate: get_estimate(pos, self.goal),
+            is_open: true,
+        });
+    }
+
+    /// Return the index of the explored node the closest to the goal
+    fn get_nearest_node(&self) -> usize {
+        self.nodes
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, node)| (node.estimate, node.cost))
+            .map(|(index, _)| index)
+            .unwrap_or(0)
+    }
+
+    /// Go up the parents from the end node to build the path
+    fn finish(&mut self, end: usize) {
+        let mut path = Vec::new();
+        let mut current = Some(end);
+        while let Some(index) = current {
+            path.push(self.nodes[index].pos);
+            current = self.nodes[index].parent;
+        }
+        path.reverse();
+
+        self.nodes.clear();
+        self.result = Some(path);
+    }
+}
+
+/// Return the Manhattan distance between the two blocks
+fn get_estimate(pos: Vector3<isize>, goal: Vector3<isize>) -> u16 {
+    let distance = pos - goal;
+    (distance.x.abs() + distance.y.abs() + distance.z.abs()) as u16
+}
@@ -0,0 +1,243 @@
+use nalgebra::Vector3;
+
+use crate::{
+    constants::{
+        BlockType,
+        world::{BUTTON_PRESS_DURATION, REDSTONE_TORCH_DELAY},
+    },
+    renderer::mesh::QuadDir,
+    world::{World, block_state::BlockState},
+};
+
+/// The power of a lever, a button, a torch or a repeater. The wire loses 1 for each block.
+pub const REDSTONE_MAX_POWER: u8 = 15;
+
+/// Repeater state value: vvvvv
+/// bit 4 : set if the repeater is powered
+/// bits 0-1 : the delay in block ticks minus 1
+pub const REPEATER_POWERED_BIT: u8 = 0b10000;
+const REPEATER_DELAY_MASK: u8 = 0b11;
+
+impl World {
+    /// Switch the levers, press the buttons and change the delay of the repeaters.
+    /// Return true if the block reacted to the interaction.
+    pub(super) fn interact_with_redstone_component(
+        &mut self,
+        pos: Vector3<isize>,
+        block_type: BlockType,
+        state: BlockState,
+    ) -> bool {
+        match block_type {
+            BlockType::Lever => {
+                let value = if state.get_value() > 0 {
+                    0
+                } else {
+                    REDSTONE_MAX_POWER
+                };
+                self.set_block_in_world_with_state(pos, block_type, state.with_value(value));
+            }
+            BlockType::Button => {
+                if state.get_value() == 0 {
+                    self.set_block_in_world_with_state(
+                        pos,
+                        block_type,
+                        state.with_value(REDSTONE_MAX_POWER),
+                    );
+                }
+                self.schedule_block_tick(pos, BUTTON_PRESS_DURATION);
+            }
+            BlockType::Repeater => {
+                let value = state.get_value();
+                let delay = ((value & REPEATER_DELAY_MASK) + 1) & REPEATER_DELAY_MASK;
+                self.set_block_in_world_with_state(
+                    pos,
+                    block_type,
+                    state.with_value((value & !REPEATER_DELAY_MASK) | delay),
+                );
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Update the redstone component after a change around it
+    pub(super) fn update_redstone_component(
+        &mut self,
+        pos: Vector3<isize>,
+        block_type: BlockType,
+        state: BlockState,
+    ) {
+        match block_type {
+            BlockType::RedstoneWire => {
+                let power = self.get_wire_power(pos);
+                if power != state.get_value() {
+                    self.set_block_in_world_with_state(pos, block_type, state.with_value(power));
+                }
+            }
+            BlockType::RedstoneLamp => {
+                let lit = self.is_receiving_redstone_power(pos);
+                if lit != (state.get_value() > 0) {
+                    let value = if lit { REDSTONE_MAX_POWER } else { 0 };
+                    self.set_block_in_world_with_state(pos, block_type, state.with_value(value));
+                }
+            }
+            // Torches and repeaters react after a delay
+            BlockType::RedstoneTorch => {
+                if self.is_torch_lit(pos) != (state.get_value() > 0) {
+                    self.schedule_block_tick(pos, REDSTONE_TORCH_DELAY);
+                }
+            }
+            BlockType::Repeater => {
+                let powered = state.get_value() & REPEATER_POWERED_BIT != 0;
+                if self.is_repeater_input_powered(pos, state) != powered {
+                    let delay = (state.get_value() & REPEATER_DELAY_MASK) as u32 + 1;
+                    self.schedule_block_tick(pos, delay);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Apply the delayed changes of the redstone components
+    pub(super) fn on_redstone_tick(
+        &mut self,
+        pos: Vector3<isize>,
+        block_type: BlockType,
+        state: BlockState,
+    ) {
+        match block_type {
+            BlockType::RedstoneTorch => {
+                let value = if self.is_torch_lit(pos) {
+                    REDSTONE_MAX_POWER
+                } else {
+                    0
+                };
+                if value != state.get_value() {
+                    self.set_block_in_world_with_state(pos, block_type, state.with_value(value));
+                }
+            }
+            BlockType::Repeater => {
+                let value = if self.is_repeater_input_powered(pos, state) {
+                    state.get_value() | REPEATER_POWERED_BIT
+                } else {
+                    state.get_value() & !REPEATER_POWERED_BIT
+                };
+                if value != state.get_value() {
+                    self.set_block_in_world_with_state(pos, block_type, state.with_value(value));
+                }
+            }
+            BlockType::Button if state.get_value() > 0 => {
+                self.set_block_in_world_with_state(pos, block_type, state.with_value(0));
+            }
+            _ => {}
+        }
+    }
+
+    /// Request an update of the blocks that can be powered through the neighbors of the given position.
+    /// Used when a redstone component changed because solid blocks transmit its power.
+    pub(super) fn notify_redstone_neighbors(&mut self, pos: Vector3<isize>) {
+        for dir in QuadDir::ALL {
+            self.notify_neighbors(pos + dir.get_normal_vector());
+        }
+    }
+
+    /// Return true if the block at the given position receives power from one of its neighbors
+    pub fn is_receiving_redstone_power(&self, pos: Vector3<isize>) -> bool {
+        QuadDir::ALL
+            .iter()
+            .any(|dir| self.get_power_emitted_to(pos + dir.get_normal_vector(), pos) > 0)
+    }
+
+    /// The wire takes the strongest power around it. The power decreases by 1 from a wire to the next one.
+    fn get_wire_power(&self, pos: Vector3<isize>) -> u8 {
+        let mut power = 0;
+        for dir in QuadDir::ALL {
+            let neighbor_pos = pos + dir.get_normal_vector();
+            let neighbor_power = if self.chunks_manager.get_block_in_world(neighbor_pos)
+                == Some(BlockType::RedstoneWire)
+            {
+                self.get_state(neighbor_pos).get_value().saturating_sub(1)
+            } else {
+                self.get_power_emitted_to(neighbor_pos, pos)
+            };
+            power = power.max(neighbor_power);
+        }
+        power
+    }
+
+    /// A torch is turned off when the block it stands on is powered
+    fn is_torch_lit(&self, pos: Vector3<isize>) -> bool {
+        self.get_power_emitted_to(pos - Vector3::new(0, 1, 0), pos) == 0
+    }
+
+    /// The repeater input is the block behind it
+    fn is_repeater_input_powered(&self, pos: Vector3<isize>, state: BlockState) -> bool {
+        state.get_facing().is_some_and(|facing| {
+            self.get_power_emitted_to(pos - facing.get_normal_vector(), pos) > 0
+        })
+    }
+
+    /// Return the power given by the block at `from` to its neighbor at `to`.
+    /// Full cubes transmit the power of the components around them.
+    fn get_power_emitted_to(&self, from: Vector3<isize>, to: Vector3<isize>) -> u8 {
+        let Some(block_type) = self.chunks_manager.get_block_in_world(from) else {
+            return 0;
+        };
+
+        if block_type.is_full_cube() {
+            // A block powered by a wire can't power another wire
+            let to_wire =
+                self.chunks_manager.get_block_in_world(to) == Some(BlockType::RedstoneWire);
+            self.get_block_power(from, !to_wire)
+        } else {
+            self.get_component_power(from, block_type, to)
+        }
+    }
+
+    /// Return the power a full cube gets from the components around it
+    fn get_block_power(&self, pos: Vector3<isize>, include_wires: bool) -> u8 {
+        let mut power = 0;
+        for dir in QuadDir::ALL {
+            let neighbor_pos = pos + dir.get_normal_vector();
+            match self.chunks_manager.get_block_in_world(neighbor_pos) {
+                Some(BlockType::RedstoneWire) if !include_wires => {}
+                Some(neighbor) if !neighbor.is_full_cube() => {
+                    power = power.max(self.get_component_power(neighbor_pos, neighbor, pos));
+                }
+                _ => {}
+            }
+        }
+        power
+    }
+
+    /// Return the power given by the component at `from` to its neighbor at `to`
+    fn get_component_power(
+        &self,
+        from: Vector3<isize>,
+        block_type: BlockType,
+        to: Vector3<isize>,
+    ) -> u8 {
+        let state = self.get_state(from);
+        match block_type {
+            BlockType::RedstoneWire | BlockType::Lever | BlockType::Button => state.get_value(),
+            // A torch doesn't power the block it stands on
+            BlockType::RedstoneTorch if to != from - Vector3::new(0, 1, 0) => state.get_value(),
+            // A repeater only powers the block in front of it
+            BlockType::Repeater
+                if state.get_value() & REPEATER_POWERED_BIT != 0
+                    && state
+                        .get_facing()
+                        .is_some_and(|facing| from + facing.get_normal_vector() == to) =>
+            {
+                REDSTONE_MAX_POWER
+            }
+            _ => 0,
+        }
+    }
+
+    fn get_state(&self, pos: Vector3<isize>) -> BlockState {
+        self.chunks_manager
+            .get_block_state_in_world(pos)
+            .unwrap_or(BlockState::DEFAULT)
+    }
+}
@@ -5,8 +5,9 @@ use rand_core::{RngCore, SeedableRng};
 use rand_xorshift::XorShiftRng;
 
 use crate::{
-    constants::world::*,
+    constants::{BlockType, world::*},
     world::{
+        block_state::BlockState,
         chunk_manager::ChunksManager,
         structures::{Structure, TREE1},
     },
@@ -109,19 +110,47 @@ impl WorldGenerator {
                         world_pos - Vector3::new(1, 0, 1),
                         Vector3::new(1, 0, 1),
                     );
+                } else if rng.next_u32() < u32::MAX / WILD_CARROTS_CHANCE
+                    && chunks_manager.get_block_in_world(world_pos) == Some(BlockType::Air)
+                    && chunks_manager.get_block_in_world(world_pos - Vector3::new(0, 1, 0))
+                        == Some(BlockType::Grass)
+                {
+                    // Wild carrots are already grown
+                    chunks_manager.set_block_in_world_with_state(
+                        world_pos,
+                        BlockType::Carrots,
+                        BlockState::DEFAULT.with_value(CROP_GROWTH_STAGES - 1),
+                    );
+                }
+            }
+        }
+
+        // Scatter the ores in the stone
+        let chunk = chunks_manager.get_chunk_at_pos_mut(chunk_pos).unwrap();
+        for x in 0..CHUNK_SIZE_I {
+            for y in 0..CHUNK_SIZE_I {
+                for z in 0..CHUNK_SIZE_I {
+                    if chunk.get_at_unchecked(Vector3::new(x, y, z)) == BlockType::Stone
+                        && rng.next_u32() < u32::MAX / IRON_ORE_CHANCE
+                    {
+                        chunk.set_at(
+                            Vector3::new(x as usize, y as usize, z as usize),
+                            BlockType::IronOre,
+                        );
+                    }
                 }
             }
         }
     }
 
-    /// Place a structure only if there is enough space
+    /// Place a structure only if there is enough space. Return true if the structure has been placed
     pub fn place_struct_check_space(
         &self,
         chunks_manager: &mut ChunksManager,
         structure: &'static Structure,
         pos: Vector3<isize>,
         margins: Vector3<isize>,
-    ) {
+    ) -> bool {
         for y in (-margins.y)..structure.size.y as isize + margins.y {
             for x in (-margins.x)..structure.size.x as isize + margins.x {
                 for z in (-margins.z)..structure.size.z as isize + margins.z {
@@ -129,12 +158,13 @@ impl WorldGenerator {
                         .get_block_in_world(pos + Vector3::new(x as isize, y as isize, z as isize))
                         .is_none_or(|b| b.is_air())
                     {
-                        return;
+                        return false;
                     }
                 }
             }
         }
         self.place_struct(chunks_manager, structure, pos);
+        true
     }
 
     pub fn place_struct(